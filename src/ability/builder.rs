@@ -1,113 +1,320 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 use bevy::prelude::*;
+use serde::Deserialize;
 
 use crate::{
-    ability::{shape::AbilityShape, Ability, TagInfo},
+    ability::{definition::AbilityDefinition, scaled_amount, shape::AbilityShape, TagInfo},
+    area::timeline::CastStage,
     buff::BuffInfo,
-    crowd_control::{CCInfo, CCKind},
-    stats::Stat,
+    stats::{Attributes, Stat},
 };
 
-impl Ability {
+pub const MAX_RANK: u8 = 5;
+
+impl AbilityDefinition {
     fn get_base_info(&self) -> AbilityInfo {
-        use Ability::*;
-        match self {
-            Frostbolt => AbilityInfo {
-                cooldown: 4.5,
-                cost: 0,
-                stages: HashMap::from([(
-                    Trigger::Cast,
-                    AbilityStage::DeployArea(DeployStage {
-                        origin: CastOrigin::Caster,
-                        shape: AbilityShape::Rectangle {
-                            length: 0.8,
-                            width: 0.5,
-                        },
-                        tags: vec![
-                            TagInfo::Damage(38.0),
-                            TagInfo::CC(CCInfo {
-                                cckind: CCKind::Stun,
-                                duration: 1.0,
-                            }),
-                            TagInfo::Buff(BuffInfo {
-                                stat: Stat::Health.into(),
-                                amount: 10.0,
-                                duration: 10.0,
-                                ..default()
-                            }),
-                        ],
-                        movement: Some(Path {
-                            kind: PathKind::Straight,
-                            speed: 1.0,
-                            lifetime: 2.0,
-                        }),
-                    }),
-                )]),
+        // lobs always come down on the reticle
+        let origin = if self.on_reticle || matches!(self.path, PathKind::Arc) {
+            CastOrigin::Reticle
+        } else {
+            CastOrigin::Caster
+        };
+        let stage = match &self.mobility {
+            // whatever tags there are ride along with the caster
+            Some(mobility) => AbilityStage::Mobility {
+                mobility: mobility.clone(),
+                area: (!self.tags.is_empty()).then(|| DeployStage {
+                    origin: CastOrigin::Caster,
+                    shape: self.shape.clone(),
+                    tags: self.tags.clone(),
+                    movement: None,
+                }),
             },
-            _ => AbilityInfo::default(),
+            None => AbilityStage::DeployArea(DeployStage {
+                origin,
+                shape: self.shape.clone(),
+                tags: self.tags.clone(),
+                movement: Some(Path {
+                    kind: self.path.clone(),
+                    speed: self.speed,
+                    lifetime: self.deployed_lifetime(),
+                }),
+            }),
+        };
+        let mut stages = HashMap::from([(Trigger::Cast, stage)]);
+        for delayed in self.delayed.iter() {
+            let origin = match delayed.origin {
+                StageOrigin::Caster => CastOrigin::Caster,
+                StageOrigin::Reticle => CastOrigin::Reticle,
+                StageOrigin::Following => CastOrigin::Following,
+            };
+            stages.insert(
+                Trigger::TimeDelay(delayed.delay),
+                AbilityStage::DeployArea(DeployStage {
+                    origin,
+                    shape: delayed.shape.clone(),
+                    tags: delayed.tags.clone(),
+                    movement: None,
+                }),
+            );
         }
-    }
-
-    fn get_changes_per_rank(&self) -> Vec<PerRankChange> {
-        use Ability::*;
-        use PerRankChange::*;
-        match self {
-            Frostbolt => vec![Cooldown(1.0), BaseDamage(40)],
-            _ => Vec::new(),
+        if let Some(recast) = &self.recast {
+            let stage = match &recast.stage {
+                RecastStage::Detonate { shape, tags, .. } => AbilityStage::DeployArea(DeployStage {
+                    origin: CastOrigin::PreviousAreas,
+                    shape: shape.clone(),
+                    tags: tags.clone(),
+                    movement: None,
+                }),
+                // distance is only known once we see how far the first cast went
+                RecastStage::Return { speed } => AbilityStage::Mobility {
+                    mobility: MobilityStage {
+                        kind: MobilityKind::Dash { speed: *speed },
+                        omni: false,
+                        distance: 0.0,
+                        wall_pen: false,
+                    },
+                    area: None,
+                },
+            };
+            stages.insert(Trigger::Recast, stage);
+        }
+        if let Some(on_collision) = &self.on_collision {
+            stages.insert(
+                Trigger::Collision,
+                AbilityStage::DeployArea(DeployStage {
+                    origin: CastOrigin::Impact,
+                    shape: on_collision.shape.clone(),
+                    tags: on_collision.tags.clone(),
+                    movement: None,
+                }),
+            );
+        }
+        if let Some(buff) = &self.passive {
+            stages.insert(Trigger::Passive, AbilityStage::Buff(buff.clone()));
+        }
+        AbilityInfo {
+            cooldown: self.cooldown,
+            cost: self.cost,
+            timeline: self.timeline.clone(),
+            stages,
         }
     }
 
-    fn get_overrides(&self) -> Vec<Override> {
-        Vec::new()
-    }
-
-    fn get_info_at_rank(&self, rank: u32) -> AbilityInfo {
+    pub fn get_info_at_rank(&self, rank: u8) -> AbilityInfo {
         let mut info = self.get_base_info();
 
-        let changes = self.get_changes_per_rank();
-        for change in changes {
-            let diff = change.get_at_rank(rank);
-            // subtract or add diff to relevant info field
-            // need a method on abilityinfo that matches PerRankChange
+        for change in self.ranks.iter() {
+            info.add_change(change, change.get_at_rank(rank));
+        }
+
+        // overrides apply from their rank onwards, later ranks win
+        let mut overrides = self.overrides.iter().filter(|o| o.rank <= rank).collect::<Vec<_>>();
+        overrides.sort_by_key(|o| o.rank);
+        for o in overrides {
+            info.set_change(&o.change);
         }
         info
     }
 }
 
 // Need to put all ranks into here for good tooltips
+#[derive(Clone, Debug)]
 pub struct AbilityBlueprint {
     pub blueprint: HashMap<u8, AbilityInfo>,
 }
 
 impl AbilityBlueprint {
-    pub fn new(ability: Ability) -> Self {
+    pub fn new(definition: &AbilityDefinition) -> Self {
         let mut blueprint = HashMap::new();
-        for i in 1..=5 {
-            blueprint.insert(i as u8, ability.get_info_at_rank(i));
+        for i in 1..=MAX_RANK {
+            blueprint.insert(i, definition.get_info_at_rank(i));
         }
         Self { blueprint }
     }
+
+    /// Rank 0 is unlearned, it reads as rank 1 so tooltips have something to show.
+    pub fn at_rank(&self, rank: u8) -> &AbilityInfo {
+        let rank = rank.clamp(1, MAX_RANK);
+        self.blueprint.get(&rank).expect("blueprint has every rank")
+    }
+
+    /// Every rank in order, for showing the progression in tooltips.
+    pub fn ranks(&self) -> impl Iterator<Item = &AbilityInfo> {
+        (1..=MAX_RANK).filter_map(|rank| self.blueprint.get(&rank))
+    }
 }
 
-#[derive(Default)]
+#[derive(Clone, Debug, Default)]
 pub struct AbilityInfo {
     pub stages: HashMap<Trigger, AbilityStage>,
     pub cooldown: f32,
     pub cost: u32,
+    pub timeline: HashMap<CastStage, f32>,
+}
+
+impl AbilityInfo {
+    pub fn cast_time(&self) -> f32 {
+        self.timeline.get(&CastStage::Input).cloned().unwrap_or_default()
+    }
+
+    /// Unscaled damage of the cast stage.
+    pub fn base_damage(&self) -> f32 {
+        let Some(deploy) = self.deploy() else { return 0.0 };
+        deploy
+            .tags
+            .iter()
+            .map(|tag| match tag {
+                TagInfo::Damage { base, .. } => *base,
+                _ => 0.0,
+            })
+            .sum()
+    }
+
+    /// Damage of the cast stage with each tag resolved against the caster, same as when it fires.
+    pub fn scaled_damage(&self, attributes: &Attributes) -> f32 {
+        let Some(deploy) = self.deploy() else { return 0.0 };
+        deploy
+            .tags
+            .iter()
+            .map(|tag| match tag {
+                TagInfo::Damage { base, scaling } => scaled_amount(*base, scaling, attributes),
+                _ => 0.0,
+            })
+            .sum()
+    }
+
+    /// Scaling of every damage tag in the cast stage added together, sorted by stat.
+    pub fn damage_scaling(&self) -> BTreeMap<Stat, f32> {
+        let mut total = BTreeMap::new();
+        let Some(deploy) = self.deploy() else { return total };
+        for tag in deploy.tags.iter() {
+            let TagInfo::Damage { scaling, .. } = tag else { continue };
+            for (stat, percent) in scaling.iter() {
+                *total.entry(*stat).or_default() += percent;
+            }
+        }
+        total
+    }
+
+    /// Area spawned on cast, including one carried along by mobility.
+    pub fn deploy(&self) -> Option<&DeployStage> {
+        match self.stages.get(&Trigger::Cast) {
+            Some(AbilityStage::DeployArea(deploy)) => Some(deploy),
+            Some(AbilityStage::Mobility { area, .. }) => area.as_ref(),
+            _ => None,
+        }
+    }
+
+    /// Buff the ability keeps up on its owner instead of waiting to be cast.
+    pub fn passive(&self) -> Option<&BuffInfo> {
+        match self.stages.get(&Trigger::Passive) {
+            Some(AbilityStage::Buff(info)) => Some(info),
+            _ => None,
+        }
+    }
+
+    pub fn mobility(&self) -> Option<&MobilityStage> {
+        match self.stages.get(&Trigger::Cast) {
+            Some(AbilityStage::Mobility { mobility, .. }) => Some(mobility),
+            _ => None,
+        }
+    }
+
+    // Rank changes only touch the cast stage, later stages keep the numbers they were defined with
+    fn deploy_mut(&mut self) -> Option<&mut DeployStage> {
+        match self.stages.get_mut(&Trigger::Cast) {
+            Some(AbilityStage::DeployArea(deploy)) => Some(deploy),
+            Some(AbilityStage::Mobility { area, .. }) => area.as_mut(),
+            _ => None,
+        }
+    }
+
+    // subtract or add diff to relevant info field
+    fn add_change(&mut self, change: &PerRankChange, diff: f32) {
+        use PerRankChange::*;
+        match *change {
+            Cooldown(_) => self.cooldown = (self.cooldown - diff).max(0.0),
+            CastTime(_) => {
+                let cast_time = (self.cast_time() - diff).max(0.0);
+                self.timeline.insert(CastStage::Input, cast_time);
+            }
+            Cost(_) => self.cost += diff as u32,
+            Scaling { stat, .. } => {
+                let Some(deploy) = self.deploy_mut() else { return };
+                for scaling in deploy.tags.iter_mut().filter_map(TagInfo::scaling_mut) {
+                    *scaling.entry(stat).or_default() += diff;
+                }
+            }
+            BaseDamage(_) => {
+                let Some(deploy) = self.deploy_mut() else { return };
+                for tag in deploy.tags.iter_mut() {
+                    if let TagInfo::Damage { base, .. } = tag {
+                        *base += diff;
+                    }
+                }
+            }
+            Range(_) => {
+                let Some(deploy) = self.deploy_mut() else { return };
+                let Some(ref mut path) = deploy.movement else { return };
+                if path.lifetime > 0.0 {
+                    path.speed += diff / path.lifetime;
+                }
+            }
+        }
+    }
+
+    fn set_change(&mut self, change: &PerRankChange) {
+        use PerRankChange::*;
+        match *change {
+            Cooldown(x) => self.cooldown = x,
+            CastTime(x) => {
+                self.timeline.insert(CastStage::Input, x);
+            }
+            Cost(x) => self.cost = x,
+            Scaling { stat, amount } => {
+                let Some(deploy) = self.deploy_mut() else { return };
+                for scaling in deploy.tags.iter_mut().filter_map(TagInfo::scaling_mut) {
+                    scaling.insert(stat, amount as f32);
+                }
+            }
+            BaseDamage(x) => {
+                let Some(deploy) = self.deploy_mut() else { return };
+                for tag in deploy.tags.iter_mut() {
+                    if let TagInfo::Damage { base, .. } = tag {
+                        *base = x as f32;
+                    }
+                }
+            }
+            Range(x) => {
+                let Some(deploy) = self.deploy_mut() else { return };
+                let Some(ref mut path) = deploy.movement else { return };
+                if path.lifetime > 0.0 {
+                    path.speed = x / path.lifetime;
+                }
+            }
+        }
+    }
 }
 
 // The fundemental types of abilities, sorted by how often they will likely to design around
 // (you can describe at least 80% of abilities in smite in the first 2, either make a collider or mobility)
+#[derive(Clone, Debug)]
 pub enum AbilityStage {
     DeployArea(DeployStage), // spawns a collider that does shit
-    // Mobility(MobilityStage), // Moves self (aka applies a cc to self?)
+    // Moves self, optionally dragging an area along
+    Mobility {
+        mobility: MobilityStage,
+        area: Option<DeployStage>,
+    },
     // Target(TargetStage), // Applies a buff / CC / deploy to a target, needs a target to even fire (could be combined with 'Buff')
     // Stance // (changes kit on self, maybe instead acts as a buff? nah prob a state change with a timer kinda)
     // Detonate // (affects another element, like a deployed ability or stacks of a debuff)
     Buff(BuffInfo), // Only applies a buff to self
 }
 
+#[derive(Clone, Debug)]
 pub struct DeployStage {
     pub origin: CastOrigin,
     pub shape: AbilityShape,
@@ -121,20 +328,38 @@ pub struct DeployStage {
 //     audio: AudioSink,
 // }
 
+#[derive(Clone, Debug, Deserialize)]
 pub struct MobilityStage {
     pub kind: MobilityKind,
+    /// Goes the way the caster is moving instead of the way they face.
+    #[serde(default)]
     pub omni: bool,
     pub distance: f32,
+    /// Jumps phase through walls while airborne instead of needing the height to clear them, other moves ignore it.
+    #[serde(default)]
     pub wall_pen: bool,
 }
 
+#[derive(Clone, Copy, Debug, Deserialize)]
 pub enum MobilityKind {
     Dash { speed: f32 }, // Add Path later?
     Jump { speed: f32, height: f32 },
     Teleport,
 }
 
+impl MobilityStage {
+    /// Seconds spent moving, teleports are instant.
+    pub fn duration(&self) -> f32 {
+        match self.kind {
+            MobilityKind::Dash { speed } | MobilityKind::Jump { speed, .. } => self.distance / speed.max(f32::EPSILON),
+            MobilityKind::Teleport => 0.0,
+        }
+    }
+}
+
 // Things that can change per rank
+// Cooldown and CastTime go down per rank, everything else goes up
+#[derive(Clone, Debug, Deserialize)]
 pub enum PerRankChange {
     Cooldown(f32),
     BaseDamage(u32),
@@ -147,51 +372,121 @@ pub enum PerRankChange {
 }
 
 impl PerRankChange {
-    fn get_at_rank(self, rank: u32) -> f32 {
+    fn get_at_rank(&self, rank: u8) -> f32 {
         use PerRankChange::*;
-        let per_rank = match self {
+        let per_rank = match *self {
             Scaling { amount, .. } => amount as f32,
             Cooldown(x) | CastTime(x) | Range(x) => x,
             Cost(x) | BaseDamage(x) => x as f32,
         };
-        per_rank * (rank - 1) as f32
+        per_rank * rank.saturating_sub(1) as f32
     }
 }
 
 // At this specific rank, set to this
 // In smite some abilities say 'at rank 3 this knocks up' for instance
+#[derive(Clone, Debug, Deserialize)]
 pub struct Override {
-    rank: u8,
-    change: PerRankChange,
+    pub rank: u8,
+    pub change: PerRankChange,
+}
+
+/// Follow up area spawned where an ability first hits something.
+#[derive(Clone, Debug, Deserialize)]
+pub struct CollisionStage {
+    #[serde(default)]
+    pub mask: CollisionMask,
+    pub shape: AbilityShape,
+    pub tags: Vec<TagInfo>,
+    /// How long the follow up spends Firing.
+    #[serde(default)]
+    pub duration: f32,
+}
+
+/// Extra area that waits out a delay after the cast before Firing.
+#[derive(Clone, Debug, Deserialize)]
+pub struct DelayedStage {
+    /// Milliseconds after the cast, no two delayed stages can share one.
+    pub delay: u32,
+    #[serde(default)]
+    pub origin: StageOrigin,
+    pub shape: AbilityShape,
+    pub tags: Vec<TagInfo>,
+    /// How long it spends Firing.
+    #[serde(default)]
+    pub duration: f32,
+}
+
+/// Where a delayed stage goes, Following rides along with the area the cast spawned.
+#[derive(Clone, Copy, Debug, Default, Deserialize)]
+pub enum StageOrigin {
+    #[default]
+    Caster,
+    Reticle,
+    Following,
+}
+
+/// Window after the first cast where casting again fires a second stage instead.
+#[derive(Clone, Debug, Deserialize)]
+pub struct RecastDefinition {
+    pub window: f32,
+    pub stage: RecastStage,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub enum RecastStage {
+    /// Blow up every area from the first cast that is still around.
+    Detonate {
+        shape: AbilityShape,
+        tags: Vec<TagInfo>,
+        #[serde(default)]
+        duration: f32,
+    },
+    /// Dash back to where the first cast started.
+    Return { speed: f32 },
+}
+
+/// What an area has to touch to set off its Collision stage.
+#[derive(Clone, Copy, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct CollisionMask {
+    pub enemies: bool,
+    pub allies: bool,
+    pub walls: bool,
 }
 
 // Which way abilities can fire, in order of occurence
-#[derive(PartialEq, PartialOrd, Eq, Hash)]
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd, Eq, Hash)]
 pub enum Trigger {
     Cast,
-    Collision,      // Merlin Frostbolt explosion // change to be the bitmask of walls/allies/enemies
+    Collision,      // Merlin Frostbolt explosion, what sets it off is the CollisionMask
     Recast,         // Isis Spirit ball, Thor 1
     TimeDelay(u32), //
     Passive,        // No trigger, buffing all the time
 }
 
 // Where to spawn a Deploy ability stage when cast (does it emit from the character or is it lobbed?)
-#[derive(PartialEq, PartialOrd, Eq, Hash)]
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd, Eq, Hash)]
 pub enum CastOrigin {
     Caster,
     Reticle,
-    Following(Entity), // For when something adds an area on collision?
+    Following,     // Rides along with whatever the cast spawned, or the caster, found when placed
+    Impact,        // Where the area that triggered it touched something
+    PreviousAreas, // On every area the first cast left, for recasts
 }
 
+#[derive(Clone, Debug)]
 pub struct Path {
-    kind: PathKind,
-    speed: f32,
-    lifetime: f32,
+    pub kind: PathKind,
+    pub speed: f32,
+    pub lifetime: f32,
 }
 
 // How abilities can move when spawned
+#[derive(Clone, Debug, Default, Deserialize)]
 pub enum PathKind {
+    #[default]
     Straight,
-    Arc,
-    // FollowingReticle
+    Arc, // lobbed from the caster, the area waits in Windup at the reticle until it lands
+         // FollowingReticle
 }
@@ -1,50 +1,78 @@
-use std::collections::HashMap;
-
-use bevy::{ecs::system::Res, ui::UiImage, utils::default};
+use bevy::{prelude::*, utils::HashMap};
+use strum::IntoEnumIterator;
 
 use crate::{
     ability::{
-        shape::AbilityShape,
-        timeline::{AreaTimeline, CastStage},
-        Ability, DamageType, TagInfo,
+        builder::{AbilityBlueprint, AbilityInfo},
+        definition::AbilityDefinition,
+        Ability,
     },
-    buff::BuffInfo,
-    crowd_control::{CCInfo, CCKind},
     prelude::Icons,
-    stats::Stat,
 };
 
-// All these methods can be easily replaced with calls to a DB table w/ ability info eventually
-// DB much easier for hotfixes
-impl Ability {
-    pub fn get_cost(&self) -> i32 {
-        match self {
-            Ability::Frostbolt => 0,
-            Ability::Fireball => 1,
-            Ability::Dash => 2,
-            Ability::BasicAttack => 0,
-            _ => 1,
+// Ability info is read from the definitions in assets/abilities, DB much easier for hotfixes.
+// Abilities without a loaded definition fall back to one that does nothing.
+#[derive(Resource)]
+pub struct AbilityRegistry {
+    map: HashMap<Ability, (AbilityDefinition, AbilityBlueprint)>,
+    fallback: (AbilityDefinition, AbilityBlueprint),
+}
+
+impl Default for AbilityRegistry {
+    fn default() -> Self {
+        let definition = AbilityDefinition::default();
+        let blueprint = AbilityBlueprint::new(&definition);
+        Self {
+            map: HashMap::new(),
+            fallback: (definition, blueprint),
         }
     }
+}
+
+impl AbilityRegistry {
+    pub fn get(&self, ability: &Ability) -> &AbilityDefinition {
+        &self.map.get(ability).unwrap_or(&self.fallback).0
+    }
+
+    pub fn blueprint(&self, ability: &Ability) -> &AbilityBlueprint {
+        &self.map.get(ability).unwrap_or(&self.fallback).1
+    }
+
+    /// What the ability does at the given rank, unlearned abilities show rank 1.
+    pub fn info(&self, ability: &Ability, rank: u8) -> &AbilityInfo {
+        self.blueprint(ability).at_rank(rank)
+    }
+}
 
-    pub fn get_cooldown(&self) -> f32 {
-        match self {
-            Ability::Dash => 7.,
-            Ability::Frostbolt => 3.5,
-            Ability::Fireball => 4.,
-            Ability::BasicAttack => 0.8,
-            _ => 3.,
+/// Rebuild the registry whenever a definition is loaded, edited or removed.
+pub fn update_ability_registry(
+    mut asset_events: EventReader<AssetEvent<AbilityDefinition>>,
+    definitions: Res<Assets<AbilityDefinition>>,
+    mut registry: ResMut<AbilityRegistry>,
+) {
+    if asset_events.read().count() == 0 {
+        return
+    }
+    registry.map.clear();
+    for (_, definition) in definitions.iter() {
+        if registry.map.contains_key(&definition.ability) {
+            warn!("multiple definitions found for {}", definition.ability);
         }
+        let blueprint = AbilityBlueprint::new(definition);
+        registry.map.insert(definition.ability, (definition.clone(), blueprint));
     }
+}
 
-    pub fn on_reticle(&self) -> bool {
-        match self {
-            Ability::Fireball => false,
-            Ability::Bomb => true,
-            _ => false,
+/// Once loading is done, call out every ability whose definition is missing or failed to load.
+pub fn report_missing_definitions(definitions: Res<Assets<AbilityDefinition>>) {
+    for ability in Ability::iter() {
+        if !definitions.iter().any(|(_, definition)| definition.ability == ability) {
+            error!("no definition loaded for {}, it will do nothing", ability);
         }
     }
+}
 
+impl Ability {
     pub fn get_image(&self, icons: &Res<Icons>) -> UiImage {
         let image = match self {
             Ability::Frostbolt => &icons.frostbolt,
@@ -55,136 +83,4 @@ impl Ability {
         };
         image.clone().into()
     }
-
-    pub fn get_name(&self) -> String {
-        let str = match self {
-            Ability::Frostbolt => "Frostbolt",
-            Ability::Fireball => "Fireball",
-            Ability::Bomb => "Rain of Fire",
-            Ability::Dash => "Driving Strike",
-            _ => "Ability",
-        };
-        str.to_string()
-    }
-
-    pub fn get_description(&self) -> String {
-        let str = match self {
-            Ability::Frostbolt => "Cold as fuck",
-            Ability::Dash => {
-                "Hercules delivers a mighty strike, driving all enemies back, 
-                damaging and Stunning them. Hercules is immune to Knockback during the dash."
-            }
-            Ability::Bomb => "Gamer move",
-            _ => "A very boring attack",
-        };
-        str.to_string()
-    }
-
-    pub fn get_damage_type(&self) -> DamageType {
-        match self {
-            Ability::Frostbolt => DamageType::Magical,
-            Ability::Fireball => DamageType::Magical,
-            Ability::Bomb => DamageType::Physical,
-            _ => DamageType::True,
-        }
-    }
-
-    pub fn get_timeline_blueprint(&self) -> HashMap<CastStage, f32> {
-        let map = match self {
-            Ability::Bomb => vec![
-                (CastStage::Input, 0.1),
-                (CastStage::Casted, 0.1),
-                (CastStage::Windup, 0.2),
-                (CastStage::Firing, 2.0),
-                (CastStage::Spindown, 0.2),
-            ],
-            _ => vec![
-                (CastStage::Input, 0.05),
-                (CastStage::Casted, 0.1),
-                (CastStage::Windup, 0.1),
-                (CastStage::Firing, 1.0),
-                (CastStage::Spindown, 0.1),
-            ],
-        };
-        map.into_iter().collect()
-    }
-
-    pub fn get_area_timeline(&self) -> AreaTimeline {
-        let map = self.get_timeline_blueprint();
-        AreaTimeline::new_at_stage(map, CastStage::Input)
-    }
-
-    pub fn get_deployed_lifetime(&self) -> f32 {
-        self.get_timeline_blueprint()
-            .iter()
-            .filter(|(stage, _)| **stage != CastStage::Input && **stage != CastStage::Casted)
-            .fold(0.0, |x, i| x + i.1)
-    }
-
-    pub fn get_speed(&self) -> f32 {
-        match self {
-            Ability::Frostbolt => 18.0,
-            Ability::Fireball => 22.0,
-            Ability::Bomb => 6.0,
-            Ability::BasicAttack => 30.0,
-            Ability::Dash => 10.0,
-            _ => 0.0,
-        }
-    }
-
-    pub fn get_shape(&self) -> AbilityShape {
-        match self {
-            Ability::Frostbolt => AbilityShape::Rectangle {
-                length: 0.8,
-                width: 0.5,
-            },
-            Ability::Fireball => AbilityShape::Arc {
-                radius: 1.,
-                angle: 360.,
-            },
-            Ability::Bomb => AbilityShape::Arc {
-                radius: 1.5,
-                angle: 360.,
-            },
-            Ability::BasicAttack => AbilityShape::default(),
-            Ability::Dash => todo!(),
-            _ => AbilityShape::default(),
-        }
-    }
-
-    pub fn is_mobility(&self) -> bool {
-        match self {
-            Ability::Dash => true,
-            _ => false,
-        }
-    }
-
-    pub fn get_tags(&self) -> Vec<TagInfo> {
-        match self {
-            Ability::Frostbolt => vec![
-                TagInfo::Damage(38.0),
-                TagInfo::CC(CCInfo {
-                    cckind: CCKind::Stun,
-                    duration: 1.0,
-                }),
-                TagInfo::Buff(BuffInfo {
-                    stat: Stat::Health.into(),
-                    amount: 10.0,
-                    duration: 10.0,
-                    ..default()
-                }),
-            ],
-            Ability::Fireball => vec![TagInfo::Damage(11.0)],
-            Ability::Bomb => vec![TagInfo::Damage(16.0)],
-            Ability::BasicAttack => vec![TagInfo::Damage(11.0)],
-            Ability::Dash => vec![
-                TagInfo::Damage(25.0),
-                TagInfo::CC(CCInfo {
-                    cckind: CCKind::Root,
-                    duration: 1.0,
-                }),
-            ],
-            _ => vec![TagInfo::Damage(100.0)],
-        }
-    }
 }
@@ -0,0 +1,295 @@
+//! Data-driven ability definitions.
+//!
+//! Every tunable property of an ability lives in a `.ability.ron` file under `assets/abilities`,
+//! so abilities can be tuned without recompiling. A new ability still needs its own `Ability` variant.
+
+use std::collections::{HashMap, HashSet};
+
+use bevy::{
+    asset::{io::Reader, AssetLoader, AsyncReadExt, LoadContext},
+    utils::BoxedFuture,
+};
+use serde::Deserialize;
+
+use crate::{
+    ability::{
+        builder::{
+            CollisionStage, DelayedStage, DeployStage, MobilityStage, Override, PathKind, PerRankChange,
+            RecastDefinition,
+        },
+        shape::AbilityShape,
+        ticks::TickBehavior,
+        Ability, CanCrit, DamageType, MaxTargetsHit, TagInfo, TargetFilter, TargetsHittable, TargetsInArea, Targetter,
+        UniqueTargetsHit,
+    },
+    area::timeline::{AreaTimeline, CastStage},
+    buff::BuffInfo,
+    prelude::*,
+};
+
+#[derive(Asset, TypePath, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct AbilityDefinition {
+    pub ability: Ability,
+    pub name: String,
+    pub description: String,
+    pub cost: u32,
+    pub cooldown: f32,
+    pub speed: f32,
+    pub shape: AbilityShape,
+    pub damage_type: DamageType,
+    pub on_reticle: bool,
+    /// How the deployed area travels, arcs land on the reticle.
+    pub path: PathKind,
+    /// Moves the caster, any tags go on an area that travels with them.
+    pub mobility: Option<MobilityStage>,
+    pub tags: Vec<TagInfo>,
+    /// Area spawned where this first hits something in its mask.
+    pub on_collision: Option<CollisionStage>,
+    /// Areas that follow the cast after a delay, like pulses of a slam.
+    pub delayed: Vec<DelayedStage>,
+    /// Second stage fired by casting again before the window closes.
+    pub recast: Option<RecastDefinition>,
+    /// Roll the caster's crit chance on damage, basic attacks always do.
+    pub crits: bool,
+    /// Buff kept up on the owner for as long as the ability is learned.
+    pub passive: Option<BuffInfo>,
+    pub timeline: HashMap<CastStage, f32>,
+    pub components: Vec<AreaComponent>,
+    /// Applied once for every rank above the first.
+    pub ranks: Vec<PerRankChange>,
+    /// Set a value outright once the ability reaches a rank.
+    pub overrides: Vec<Override>,
+}
+
+impl Default for AbilityDefinition {
+    fn default() -> Self {
+        Self {
+            ability: Ability::default(),
+            name: "Ability".to_string(),
+            description: "A very boring attack".to_string(),
+            cost: 1,
+            cooldown: 3.0,
+            speed: 0.0,
+            shape: AbilityShape::default(),
+            damage_type: DamageType::True,
+            on_reticle: false,
+            path: PathKind::Straight,
+            mobility: None,
+            tags: Vec::new(),
+            on_collision: None,
+            delayed: Vec::new(),
+            recast: None,
+            crits: false,
+            passive: None,
+            timeline: HashMap::from([
+                (CastStage::Input, 0.05),
+                (CastStage::Casted, 0.1),
+                (CastStage::Windup, 0.1),
+                (CastStage::Firing, 1.0),
+                (CastStage::Spindown, 0.1),
+            ]),
+            components: Vec::new(),
+            ranks: Vec::new(),
+            overrides: Vec::new(),
+        }
+    }
+}
+
+impl AbilityDefinition {
+    pub fn deployed_lifetime(&self) -> f32 {
+        self.timeline
+            .iter()
+            .filter(|(stage, _)| **stage != CastStage::Input && **stage != CastStage::Casted)
+            .fold(0.0, |x, i| x + i.1)
+    }
+
+    pub fn hover(&self) -> impl Bundle {
+        let lifetime = self.deployed_lifetime();
+        let length = self.shape.get_length();
+        let width = self.shape.get_width();
+
+        // lobs land in one spot instead of sweeping forward
+        let travel = match self.path {
+            PathKind::Arc => 0.0,
+            PathKind::Straight => self.speed * lifetime,
+        };
+        let length_with_movement = length + travel;
+
+        let offset = if self.on_reticle {
+            Vec3::new(0.0, 0.0, -(length_with_movement - length) / 2.0)
+        } else {
+            Vec3::new(0.0, 0.0, -length_with_movement / 2.0)
+        };
+        (
+            SpatialBundle::from_transform(Transform {
+                translation: offset,
+                ..default()
+            }),
+            Sensor,
+            Targetter,
+            AbilityShape::Rectangle {
+                length: length_with_movement,
+                width,
+            },
+            self.ability,
+        )
+    }
+
+    /// Components shared by every area this ability deploys.
+    pub fn area(&self, transform: Transform) -> impl Bundle {
+        (
+            Name::new(self.name.clone()),
+            self.ability,
+            self.shape.clone(),
+            SpatialBundle::from_transform(transform),
+            Sensor,
+            RigidBody::Kinematic,
+            AreaTimeline::new_at_stage(self.timeline.clone(), CastStage::Windup),
+            self.damage_type,
+            TargetsHittable::default(),
+            TargetsInArea::default(),
+        )
+    }
+
+    /// Area spawned by a later stage, it skips straight to Firing.
+    pub fn follow_up(&self, deploy: &DeployStage, duration: f32, translation: Vec3) -> impl Bundle {
+        (
+            Name::new(format!("{} follow up", self.name)),
+            self.ability,
+            deploy.shape.clone(),
+            SpatialBundle::from_transform(Transform::from_translation(translation)),
+            Sensor,
+            RigidBody::Kinematic,
+            AreaTimeline::new_at_stage(
+                HashMap::from([(CastStage::Firing, duration)]),
+                CastStage::Firing,
+            ),
+            self.damage_type,
+            TargetsHittable::default(),
+            TargetsInArea::default(),
+            UniqueTargetsHit::default(),
+        )
+    }
+
+    /// Delayed stages are keyed by their delay, so two sharing one would clobber each other.
+    fn duplicate_delay(&self) -> Option<u32> {
+        let mut seen = HashSet::new();
+        self.delayed
+            .iter()
+            .map(|delayed| delayed.delay)
+            .find(|delay| !seen.insert(*delay))
+    }
+
+    pub fn can_crit(&self) -> bool {
+        self.crits || self.ability == Ability::BasicAttack
+    }
+
+    /// Moves the caster, these are locked out by cripple.
+    pub fn is_mobility(&self) -> bool {
+        self.mobility.is_some()
+    }
+
+    /// A tick component decides how often the same target can be hit again.
+    pub fn has_ticks(&self) -> bool {
+        self.components.iter().any(|component| {
+            matches!(
+                component,
+                AreaComponent::TicksIndividual(_) | AreaComponent::TicksStatic(_)
+            )
+        })
+    }
+
+    pub fn add_unique_components(&self, commands: &mut Commands, entity: Entity) {
+        if self.can_crit() {
+            commands.entity(entity).insert(CanCrit);
+        }
+        for component in self.components.iter() {
+            component.insert(commands, entity);
+        }
+    }
+}
+
+/// Ability-specific components, like MaxTargets before despawn, Ticks, etc.
+#[derive(Deserialize, Clone, Debug)]
+pub enum AreaComponent {
+    MaxTargetsHit(u8),
+    UniqueTargetsHit,
+    TicksIndividual(f32),
+    TicksStatic(f32),
+    TargetClosest(u8),
+}
+
+impl AreaComponent {
+    fn insert(&self, commands: &mut Commands, entity: Entity) {
+        let mut entity = commands.entity(entity);
+        match *self {
+            AreaComponent::MaxTargetsHit(max) => entity.insert(MaxTargetsHit::new(max)),
+            AreaComponent::UniqueTargetsHit => entity.insert(UniqueTargetsHit::default()),
+            AreaComponent::TicksIndividual(interval) => entity.insert(TickBehavior::new_individual(interval)),
+            AreaComponent::TicksStatic(interval) => entity.insert(TickBehavior::new_static(interval)),
+            AreaComponent::TargetClosest(num) => entity.insert(TargetFilter::closest(num)),
+        };
+    }
+}
+
+#[derive(Default)]
+pub struct AbilityDefinitionLoader;
+
+#[derive(Debug)]
+pub enum AbilityDefinitionLoaderError {
+    Io(std::io::Error),
+    Ron(ron::error::SpannedError),
+    DuplicateDelay(u32),
+}
+
+impl std::fmt::Display for AbilityDefinitionLoaderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "could not read ability definition: {}", err),
+            Self::Ron(err) => write!(f, "could not parse ability definition: {}", err),
+            Self::DuplicateDelay(delay) => write!(f, "more than one delayed stage at {}ms", delay),
+        }
+    }
+}
+
+impl std::error::Error for AbilityDefinitionLoaderError {}
+
+impl From<std::io::Error> for AbilityDefinitionLoaderError {
+    fn from(err: std::io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+impl From<ron::error::SpannedError> for AbilityDefinitionLoaderError {
+    fn from(err: ron::error::SpannedError) -> Self {
+        Self::Ron(err)
+    }
+}
+
+impl AssetLoader for AbilityDefinitionLoader {
+    type Asset = AbilityDefinition;
+    type Settings = ();
+    type Error = AbilityDefinitionLoaderError;
+
+    fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader,
+        _settings: &'a (),
+        _load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<Self::Asset, Self::Error>> {
+        Box::pin(async move {
+            let mut bytes = Vec::new();
+            reader.read_to_end(&mut bytes).await?;
+            let definition = ron::de::from_bytes::<AbilityDefinition>(&bytes)?;
+            if let Some(delay) = definition.duplicate_delay() {
+                return Err(AbilityDefinitionLoaderError::DuplicateDelay(delay))
+            }
+            Ok(definition)
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["ability.ron"]
+    }
+}
@@ -7,20 +7,30 @@
 //! `Appliers` finalize the effects of the ability on the target entity, most commonly damaging or
 //! debuffing.
 
+use std::collections::HashMap;
+
 use bevy::prelude::*;
 use derive_more::Display;
 use leafwing_input_manager::Actionlike;
+use serde::Deserialize;
+use strum_macros::EnumIter;
 
 use crate::{
-    ability::{shape::AbilityShape, ticks::TickBehavior},
+    ability::{
+        db::{report_missing_definitions, update_ability_registry, AbilityRegistry},
+        definition::{AbilityDefinition, AbilityDefinitionLoader},
+    },
     buff::BuffInfo,
-    crowd_control::CCInfo,
+    crowd_control::{CCInfo, CCSelection, Displacement},
+    over_time::OverTimeInfo,
     prelude::*,
+    GameState,
 };
 
 pub mod builder;
 pub mod collector;
 pub mod db;
+pub mod definition;
 pub mod shape;
 pub mod ticks;
 
@@ -55,6 +65,13 @@ impl Plugin for AbilityPlugin {
                 .in_set(InGameSet::Update),
         );
 
+        app.init_asset::<AbilityDefinition>()
+            .init_asset_loader::<AbilityDefinitionLoader>()
+            .init_resource::<AbilityRegistry>();
+
+        app.add_systems(Update, update_ability_registry);
+        app.add_systems(OnExit(GameState::Loading), report_missing_definitions);
+
         /*
         app.add_systems(
             FixedUpdate,
@@ -74,7 +91,9 @@ impl Plugin for AbilityPlugin {
     }
 }
 
-#[derive(Actionlike, Component, Reflect, Clone, Copy, Debug, Default, Display, Eq, PartialEq, Hash)]
+#[derive(
+    Actionlike, Component, Reflect, Clone, Copy, Debug, Default, Display, Eq, PartialEq, Hash, EnumIter, Deserialize,
+)]
 #[reflect(Component)]
 pub enum Ability {
     Frostbolt,
@@ -86,61 +105,6 @@ pub enum Ability {
     BallistaShot,
 }
 
-impl Ability {
-    pub fn add_unique_components(&self, commands: &mut Commands, entity: Entity) {
-        match self {
-            Ability::Frostbolt => {
-                commands.entity(entity).insert(MaxTargetsHit::new(1));
-            }
-            Ability::Fireball => {
-                commands.entity(entity).insert(UniqueTargetsHit::default());
-            }
-            Ability::Bomb => {
-                commands.entity(entity).insert(TickBehavior::new_individual(0.5));
-            }
-            Ability::BasicAttack => {
-                commands
-                    .entity(entity)
-                    .insert((MaxTargetsHit::new(2), UniqueTargetsHit::default()));
-            }
-            Ability::Dash => {
-                commands.entity(entity).insert(UniqueTargetsHit::default());
-            }
-            Ability::BallistaShot => {
-                commands.entity(entity).insert(TargetFilter::closest(1));
-            }
-        }
-    }
-
-    pub fn hover(&self) -> impl Bundle {
-        let speed = self.get_speed();
-        let lifetime = self.get_deployed_lifetime();
-        let length = self.get_shape().get_length();
-        let width = self.get_shape().get_width();
-
-        let length_with_movement = length + speed * lifetime;
-
-        let offset = if self.on_reticle() {
-            Vec3::new(0.0, 0.0, -(length_with_movement - length) / 2.0)
-        } else {
-            Vec3::new(0.0, 0.0, -length_with_movement / 2.0)
-        };
-        (
-            SpatialBundle::from_transform(Transform {
-                translation: offset,
-                ..default()
-            }),
-            Sensor,
-            Targetter,
-            AbilityShape::Rectangle {
-                length: length_with_movement,
-                width,
-            },
-            *self,
-        )
-    }
-}
-
 #[derive(Component, Default, Debug, Clone, Reflect)]
 #[reflect(Component)]
 pub struct TargetsInArea {
@@ -197,20 +161,140 @@ pub struct UniqueTargetsHit {
     pub already_hit: Vec<Entity>,
 }
 
+// Damage from this area can critically strike
+#[derive(Component, Debug, Clone, Default)]
+pub struct CanCrit;
+
 #[derive(Component, Default, Deref, Clone, Debug)]
 pub struct Tags(pub Vec<TagInfo>);
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Deserialize)]
 pub enum TagInfo {
-    Heal(f32),
-    Damage(f32),
+    /// Percent of each caster stat in `scaling` is added on top of `base` when fired.
+    Heal {
+        base: f32,
+        #[serde(default)]
+        scaling: HashMap<Stat, f32>,
+    },
+    Damage {
+        base: f32,
+        #[serde(default)]
+        scaling: HashMap<Stat, f32>,
+    },
     Buff(BuffInfo),
     CC(CCInfo),
     Homing(Ability), // Once an Ability turns into a spawned entity, it is an Area, Abilities are simply blueprints rn
     ResourcePerTarget(i32),
+    Cleanse(CCSelection), // remove CC from allies
+    Purify,               // remove debuffs from allies
+    Immunity {
+        ccs: CCSelection,
+        duration: f32,
+    },
+    DamageOverTime(OverTimeInfo),
+    HealOverTime(OverTimeInfo),
+    Shield {
+        amount: f32,
+        #[serde(default)]
+        scaling: HashMap<Stat, f32>,
+        duration: f32,
+        #[serde(default)]
+        blocks: Option<DamageType>,
+    },
+    Knockback {
+        distance: f32,
+        duration: f32,
+    },
+    Pull {
+        distance: f32,
+        duration: f32,
+    },
+    Knockup {
+        height: f32,
+        duration: f32,
+    },
+    Slow {
+        percent: f32,
+        duration: f32,
+    },
+}
+
+impl TagInfo {
+    /// Damage that doesn't scale with anything.
+    pub fn damage(base: f32) -> Self {
+        TagInfo::Damage {
+            base,
+            scaling: HashMap::new(),
+        }
+    }
+
+    pub fn heal(base: f32) -> Self {
+        TagInfo::Heal {
+            base,
+            scaling: HashMap::new(),
+        }
+    }
+
+    /// Caster scaling of the tags that have one, damage, heal and shield.
+    pub fn scaling_mut(&mut self) -> Option<&mut HashMap<Stat, f32>> {
+        match self {
+            TagInfo::Damage { scaling, .. } | TagInfo::Heal { scaling, .. } | TagInfo::Shield { scaling, .. } => {
+                Some(scaling)
+            }
+            _ => None,
+        }
+    }
+
+    /// Resolve the tag's scaling against the caster, the result is a flat amount.
+    pub fn scaled(&self, attributes: &Attributes) -> TagInfo {
+        match self {
+            TagInfo::Damage { base, scaling } => TagInfo::damage(scaled_amount(*base, scaling, attributes)),
+            TagInfo::Heal { base, scaling } => TagInfo::heal(scaled_amount(*base, scaling, attributes)),
+            TagInfo::Shield {
+                amount,
+                scaling,
+                duration,
+                blocks,
+            } => TagInfo::Shield {
+                amount: scaled_amount(*amount, scaling, attributes),
+                scaling: HashMap::new(),
+                duration: *duration,
+                blocks: *blocks,
+            },
+            other => other.clone(),
+        }
+    }
+
+    /// The forced movement and its CC for displacement tags.
+    pub fn displacement(&self) -> Option<(Displacement, CCInfo)> {
+        let (displacement, duration) = match *self {
+            TagInfo::Knockback { distance, duration } => (Displacement::Knockback { distance }, duration),
+            TagInfo::Pull { distance, duration } => (Displacement::Pull { distance }, duration),
+            TagInfo::Knockup { height, duration } => (Displacement::Knockup { height }, duration),
+            _ => return None,
+        };
+        let ccinfo = CCInfo {
+            cckind: displacement.cckind(),
+            duration,
+        };
+        Some((displacement, ccinfo))
+    }
+}
+
+/// Base plus a percent of each caster stat, like 38 + 60% magical power.
+pub fn scaled_amount(base: f32, scaling: &HashMap<Stat, f32>, attributes: &Attributes) -> f32 {
+    base + scaling
+        .iter()
+        .map(|(stat, percent)| attributes.get(*stat) * percent / 100.0)
+        .sum::<f32>()
+}
+
+/// Resolve every tag against the caster at fire time.
+pub fn scale_tags(tags: &[TagInfo], attributes: &Attributes) -> Vec<TagInfo> {
+    tags.iter().map(|tag| tag.scaled(attributes)).collect()
 }
 
-#[derive(Component, Clone, Copy, PartialEq, Eq, Debug)]
+#[derive(Component, Clone, Copy, PartialEq, Eq, Debug, Deserialize)]
 pub enum DamageType {
     Physical,
     Magical,
@@ -1,13 +1,28 @@
 use std::{collections::HashMap, time::Duration};
 
 use crate::{
-    ability::{Ability, Tags, TargetsHittable, TargetsInArea, Targetter}, actor::{
+    ability::{
+        builder::{AbilityStage, CastOrigin, MobilityStage, PathKind, RecastDefinition, RecastStage, Trigger},
+        db::AbilityRegistry,
+        scale_tags, Ability, Tags, Targetter, UniqueTargetsHit,
+    },
+    actor::{
+        controller::Controller,
         player::input::{PlayerInputKeys, PlayerInputQuery},
         rank::AbilityRanks,
-    }, area::{
+    },
+    area::{
+        following::Following,
         homing::Homing,
+        impact::ImpactStage,
+        lob::Lob,
         timeline::{AreaTimeline, CastStage},
-    }, assets::MaterialPresets, camera::{OuterGimbal, Reticle}, crowd_control::{CCKind, CCMap}, mobility::Mobility, prelude::*
+    },
+    assets::MaterialPresets,
+    camera::{OuterGimbal, Reticle},
+    crowd_control::CCMap,
+    mobility::Mobility,
+    prelude::*,
 };
 
 pub struct CastPlugin;
@@ -38,6 +53,7 @@ pub fn show_targetter(
     gimbals: Query<Entity, With<OuterGimbal>>,
     targetters: Query<(Entity, &Ability), With<Targetter>>,
     presets: Res<MaterialPresets>,
+    registry: Res<AbilityRegistry>,
     mut materials: ResMut<Assets<StandardMaterial>>,
 ) {
     for (hovered, cooldowns) in &query {
@@ -65,9 +81,10 @@ pub fn show_targetter(
                 .unwrap_or(&materials.add(Color::rgb(0.4, 0.4, 0.4)))
                 .clone();
         }
-        let targetter = commands.spawn(hovered_ability.hover()).insert(handle).id();
+        let definition = registry.get(&hovered_ability);
+        let targetter = commands.spawn(definition.hover()).insert(handle).id();
 
-        if hovered_ability.on_reticle() {
+        if definition.on_reticle {
             commands.entity(targetter).set_parent(reticle_entity);
         } else {
             commands.entity(targetter).set_parent(gimbal_entity);
@@ -117,6 +134,10 @@ fn hover_and_input(
             hover.0 = None;
             continue
         }
+        // slots are being ranked up instead
+        if input.pressed(PlayerInputKeys::LEVEL_UP) {
+            continue
+        }
 
         for (index, ability_key) in input.slots().iter().enumerate() {
             let Some(slot) = Slot::from_index(index) else { continue };
@@ -175,16 +196,32 @@ fn hover_and_input(
     }
 }
 
-fn start_casting(mut actors: Query<(&CCMap, &mut Casting), Changed<Casting>>) {
-    for (cc, mut casting) in actors.iter_mut() {
-        if cc.map.contains_key(&CCKind::Silence) || cc.map.contains_key(&CCKind::Stun) {
+fn start_casting(
+    mut actors: Query<(&CCMap, &AbilityRanks, &mut Casting), Changed<Casting>>,
+    registry: Res<AbilityRegistry>,
+) {
+    for (cc, ranks, mut casting) in actors.iter_mut() {
+        if cc.blocks_casting() {
             continue
         } // play error sound for silenced
         for ability in casting.next.clone() {
             if casting.current.contains_key(&ability) {
                 continue
             }
-            casting.current.insert(ability, ability.get_area_timeline());
+            if !ranks.is_learned(&ability) {
+                continue
+            }
+            if ability == Ability::BasicAttack && cc.is_disarmed() {
+                continue
+            }
+            if registry.get(&ability).is_mobility() && cc.is_crippled() {
+                continue
+            }
+            let info = registry.info(&ability, ranks.get(&ability));
+            casting.current.insert(
+                ability,
+                AreaTimeline::new_at_stage(info.timeline.clone(), CastStage::Input),
+            );
         }
         casting.next = Vec::new();
     }
@@ -192,22 +229,47 @@ fn start_casting(mut actors: Query<(&CCMap, &mut Casting), Changed<Casting>>) {
 
 fn tick_casting(
     time: Res<Time>,
-    mut casters: Query<(&mut Casting, &mut Attributes, &mut CooldownMap, Entity)>,
+    mut casters: Query<(
+        &mut Casting,
+        &mut Attributes,
+        &mut CooldownMap,
+        &AbilityRanks,
+        Entity,
+    )>,
     mut cast_events: EventWriter<AbilityFireEvent>,
+    registry: Res<AbilityRegistry>,
 ) {
-    for (mut casting, mut attributes, mut cooldowns, entity) in casters.iter_mut() {
+    for (mut casting, mut attributes, mut cooldowns, ranks, entity) in casters.iter_mut() {
         casting.current.retain(|ability, timeline| {
             timeline.tick(time.delta());
             if timeline.stage == CastStage::Casted {
+                let info = registry.info(ability, ranks.get(ability));
+                // recasting is free and puts the ability on the cooldown it was holding
+                if let Some(window) = cooldowns.recasts.remove(ability) {
+                    cooldowns.map.insert(
+                        ability.clone(),
+                        Timer::new(
+                            Duration::from_millis((window.cooldown * 1000.) as u64),
+                            TimerMode::Once,
+                        ),
+                    );
+                    cast_events.send(AbilityFireEvent {
+                        caster: entity,
+                        ability: ability.clone(),
+                        extras: vec![AbilityExtras::Recast(window)],
+                    });
+                    return false
+                }
                 // have this check in here so you can predict when an ability will be up for skill cap
                 if cooldowns.map.contains_key(&ability) {
                     return false
                 } // play error sound for on CD
 
                 let cdr = 1.0 - (attributes.get(Stat::CooldownReduction) / 100.0);
+                let cooldown = info.cooldown * cdr;
 
                 let resource = attributes.get_mut(Stat::CharacterResource);
-                if *resource < ability.get_cost() as f32 {
+                if *resource < info.cost as f32 {
                     // if not enough resource
                     // Recoil damage and continue
                     let hp = attributes.get_mut(Stat::Health);
@@ -219,11 +281,24 @@ fn tick_casting(
                     ability: ability.clone(),
                     extras: Vec::new(),
                 });
-                *resource -= ability.get_cost() as f32;
+                *resource -= info.cost as f32;
+                // the cooldown waits for the recast or for the window to run out
+                if let Some(recast) = &registry.get(ability).recast {
+                    cooldowns.recasts.insert(
+                        ability.clone(),
+                        RecastWindow {
+                            timer: Timer::from_seconds(recast.window, TimerMode::Once),
+                            cooldown,
+                            start: Vec3::ZERO,
+                            areas: Vec::new(),
+                        },
+                    );
+                    return false
+                }
                 cooldowns.map.insert(
                     ability.clone(),
                     Timer::new(
-                        Duration::from_millis((ability.get_cooldown() * cdr * 1000.) as u64),
+                        Duration::from_millis((cooldown * 1000.) as u64),
                         TimerMode::Once,
                     ),
                 );
@@ -248,83 +323,221 @@ fn tick_cooldowns(
             timer.tick(time.delta());
             !timer.finished()
         });
+        // recasts that were never used go on cooldown
+        let mut expired = Vec::new();
+        cooldowns.recasts.retain(|ability, window| {
+            window.timer.tick(time.delta());
+            if window.timer.finished() {
+                expired.push((*ability, window.cooldown));
+            }
+            !window.timer.finished()
+        });
+        for (ability, cooldown) in expired {
+            cooldowns.map.insert(
+                ability,
+                Timer::new(
+                    Duration::from_millis((cooldown * 1000.) as u64),
+                    TimerMode::Once,
+                ),
+            );
+        }
     }
 }
 
 fn place_ability(
     mut commands: Commands,
     mut cast_events: EventReader<AbilityFireEvent>,
-    caster: Query<(&GlobalTransform, &Team, &AbilityRanks, Entity)>,
+    mut caster: Query<(
+        &GlobalTransform,
+        &Team,
+        &AbilityRanks,
+        &Attributes,
+        Option<&Controller>,
+        Option<&mut CooldownMap>,
+    )>,
+    areas: Query<&GlobalTransform>,
     reticle: Query<&GlobalTransform, With<Reticle>>,
     procmaps: Query<&ProcMap>,
+    registry: Res<AbilityRegistry>,
 ) {
     let Ok(reticle_transform) = reticle.get_single() else { return };
     for event in cast_events.read() {
         let ability = event.ability;
-        if ability.is_mobility() {
-            let Ok((_, _, _, entity)) = caster.get(event.caster) else { return };
-            commands.entity(entity).insert(Mobility::Dash);
-        } else {
-            let Ok((caster_transform, team, _ranks, _)) = caster.get(event.caster) else { return };
-            // Get ability-specific components
-            let transform = if event.ability.on_reticle() {
-                reticle_transform.compute_transform()
-            } else {
-                caster_transform.compute_transform()
-            };
+        let definition = registry.get(&ability);
+        let Ok((caster_transform, team, ranks, attributes, controller, mut cooldowns)) = caster.get_mut(event.caster)
+        else {
+            return
+        };
+        let info = registry.info(&ability, ranks.get(&ability));
+
+        if let Some(AbilityExtras::Recast(window)) = event.extras.first() {
+            match info.stages.get(&Trigger::Recast) {
+                Some(AbilityStage::DeployArea(deploy)) => {
+                    let duration = match &definition.recast {
+                        Some(RecastDefinition {
+                            stage: RecastStage::Detonate { duration, .. },
+                            ..
+                        }) => *duration,
+                        _ => 0.0,
+                    };
+                    // ranks leave the recast alone, it only scales by what its own tags ask for
+                    let tags = scale_tags(&deploy.tags, attributes);
+                    for area in window.areas.iter() {
+                        let Ok(area_transform) = areas.get(*area) else { continue };
+                        commands
+                            .spawn(definition.follow_up(deploy, duration, area_transform.translation()))
+                            .insert((team.clone(), Caster(event.caster), Tags(tags.clone())));
+                        commands.entity(*area).despawn_recursive();
+                    }
+                }
+                Some(AbilityStage::Mobility { mobility, .. }) => {
+                    let position = caster_transform.translation();
+                    let back = window.start - position;
+                    let mobility = MobilityStage {
+                        distance: Vec3::new(back.x, 0.0, back.z).length(),
+                        ..mobility.clone()
+                    };
+                    let destination = Vec3::new(window.start.x, position.y, window.start.z);
+                    commands
+                        .entity(event.caster)
+                        .insert(Mobility::to(&mobility, position, destination, None));
+                }
+                _ => {}
+            }
+            continue
+        }
+        let mobility = info.mobility();
+
+        // Get ability-specific components
+        let transform = match info.deploy().map(|deploy| deploy.origin) {
+            Some(CastOrigin::Reticle) if mobility.is_none() => reticle_transform.compute_transform(),
+            _ => caster_transform.compute_transform(),
+        };
 
-            // TODO if ability actually spawns something, which is going to be like 80% of the time
-            // other cases include Zeus Detonate, self buffs, mobility
+        // TODO if ability actually spawns something, which is going to be like 80% of the time
+        // other cases include Zeus Detonate, self buffs
+        let spawned = info.deploy().map(|deploy| {
             let spawned = commands
-                .spawn((
-                    Name::new(ability.get_name()),
-                    ability,
-                    ability.get_shape(),
-                    SpatialBundle::from_transform(transform.clone()),
-                    Sensor,
-                    RigidBody::Kinematic,
-                    // Apply team and caster components for figuring out damage
-                    team.clone(),
-                    Caster(event.caster),
-                    AreaTimeline::new_at_stage(ability.get_timeline_blueprint(), CastStage::Windup),
-                    ability.get_damage_type(),
-                    TargetsHittable::default(),
-                    TargetsInArea::default(),
-                ))
+                .spawn(definition.area(transform))
+                // Apply team and caster components for figuring out damage
+                .insert((team.clone(), Caster(event.caster), deploy.shape.clone()))
                 .id();
 
-            if ability.get_speed() > 1.0 {
-                let direction = transform.rotation * -Vec3::Z;
-                commands
-                    .entity(spawned)
-                    .insert(LinearVelocity(direction * ability.get_speed()));
+            if let Some(path) = &deploy.movement {
+                match path.kind {
+                    PathKind::Straight => {
+                        if path.speed > 1.0 {
+                            let direction = transform.rotation * -Vec3::Z;
+                            commands.entity(spawned).insert(LinearVelocity(direction * path.speed));
+                        }
+                    }
+                    // telegraph at the target for as long as the lob is in the air
+                    PathKind::Arc => {
+                        let start = caster_transform.translation();
+                        let flight = Lob::flight_time(start, transform.translation, path.speed);
+                        let mut blueprint = info.timeline.clone();
+                        blueprint.insert(CastStage::Windup, flight);
+                        commands
+                            .entity(spawned)
+                            .insert(AreaTimeline::new_at_stage(blueprint, CastStage::Windup));
+                        commands.spawn((
+                            Name::new(format!("{} lob", definition.name)),
+                            SpatialBundle::from_transform(Transform::from_translation(start)),
+                            Lob::new(start, transform.translation, flight),
+                        ));
+                    }
+                }
             }
 
-            //let rank = ranks.map.get(&event.ability).cloned().unwrap_or_default();
-            //let scaling = rank.current as u32 * event.ability.get_scaling();
+            commands
+                .entity(spawned)
+                .insert(Tags(scale_tags(&deploy.tags, attributes)));
 
-            // TODO Scale these tags with ranks appropriately
-            commands.entity(spawned).insert(Tags(ability.get_tags()));
+            // like MaxTargets before despawn, Ticks, etc.
+            definition.add_unique_components(&mut commands, spawned);
 
-            // like MaxTargets before despawn, Ticks, etc. Rework later somehow
-            ability.add_unique_components(&mut commands, spawned);
+            if let Some(on_collision) = &definition.on_collision {
+                commands.entity(spawned).insert(ImpactStage {
+                    mask: on_collision.mask,
+                    rank: ranks.get(&ability),
+                });
+            }
 
             for extra in event.extras.iter() {
                 match extra {
                     AbilityExtras::Homing(target) => {
                         commands.entity(spawned).insert(Homing(*target));
                     }
+                    AbilityExtras::Recast(_) => (),
                 }
             }
+            spawned
+        });
 
-            // Apply special procs from the caster's proc list component (qin sais, exe, etc)
-            if let Ok(procmap) = procmaps.get(event.caster) {
-                if let Some(behaviors) = procmap.0.get(&event.ability) {
-                    for behavior in behaviors {
-                        match behavior {
-                            AbilityBehavior::Homing => (),
-                            AbilityBehavior::OnHit => (),
-                        }
+        // later stages wait out their delay in Windup on the same timeline as any other area
+        for delayed in definition.delayed.iter() {
+            let Some(AbilityStage::DeployArea(deploy)) = info.stages.get(&Trigger::TimeDelay(delayed.delay)) else {
+                continue
+            };
+            let transform = match deploy.origin {
+                CastOrigin::Reticle => reticle_transform.compute_transform(),
+                _ => caster_transform.compute_transform(),
+            };
+            let blueprint = HashMap::from([
+                (CastStage::Windup, delayed.delay as f32 / 1000.0),
+                (CastStage::Firing, delayed.duration),
+            ]);
+            let area = commands
+                .spawn(definition.area(transform))
+                .insert((
+                    team.clone(),
+                    Caster(event.caster),
+                    deploy.shape.clone(),
+                    AreaTimeline::new_at_stage(blueprint, CastStage::Windup),
+                    Tags(scale_tags(&deploy.tags, attributes)),
+                ))
+                .id();
+            // same components as the first stage, without ticks a pulse hits each target once
+            definition.add_unique_components(&mut commands, area);
+            if !definition.has_ticks() {
+                commands.entity(area).insert(UniqueTargetsHit::default());
+            }
+            if let CastOrigin::Following = deploy.origin {
+                commands.entity(area).insert(Following(spawned.unwrap_or(event.caster)));
+            }
+        }
+
+        // remember what the first cast left behind for the recast
+        if let Some(window) = cooldowns
+            .as_mut()
+            .and_then(|cooldowns| cooldowns.recasts.get_mut(&ability))
+        {
+            window.start = transform.translation;
+            window.areas.extend(spawned);
+        }
+
+        if let Some(mobility) = mobility {
+            let facing = transform.rotation * -Vec3::Z;
+            let direction = match controller {
+                Some(controller) if mobility.omni && controller.direction != Vec3::ZERO => controller.direction,
+                _ => facing,
+            };
+            commands.entity(event.caster).insert(Mobility::new(
+                mobility,
+                transform.translation,
+                direction,
+                reticle_transform.translation(),
+                spawned,
+            ));
+        }
+
+        // Apply special procs from the caster's proc list component (qin sais, exe, etc)
+        if let Ok(procmap) = procmaps.get(event.caster) {
+            if let Some(behaviors) = procmap.0.get(&event.ability) {
+                for behavior in behaviors {
+                    match behavior {
+                        AbilityBehavior::Homing => (),
+                        AbilityBehavior::OnHit => (),
                     }
                 }
             }
@@ -351,13 +564,27 @@ pub struct AbilityFireEvent {
 
 pub enum AbilityExtras {
     Homing(Entity),
-    // idk cant think of any others rn
+    Recast(RecastWindow),
 }
 
 #[derive(Component, Reflect, Default, Debug, Clone)]
 #[reflect]
 pub struct CooldownMap {
     pub map: HashMap<Ability, Timer>,
+    #[reflect(ignore)]
+    pub recasts: HashMap<Ability, RecastWindow>,
+}
+
+/// Time left to recast an ability, its cooldown is held until this closes.
+#[derive(Debug, Clone)]
+pub struct RecastWindow {
+    pub timer: Timer,
+    /// Cooldown to start once the window closes, cooldown reduction already applied.
+    pub cooldown: f32,
+    /// Where the first cast started, for returning.
+    pub start: Vec3,
+    /// Areas from the first cast, for detonating.
+    pub areas: Vec<Entity>,
 }
 
 #[derive(Component)]
@@ -1,4 +1,4 @@
-use crate::prelude::*;
+use crate::{crowd_control::CCMap, mobility::Mobility, prelude::*};
 
 pub struct ControllerPlugin;
 impl Plugin for ControllerPlugin {
@@ -31,8 +31,23 @@ impl Controller {
     }
 }
 
-pub fn controller_movement(mut controllers: Query<(&mut ExternalImpulse, &LinearVelocity, &Mass, &Controller)>) {
-    for (mut impulse, velocity, mass, controller) in &mut controllers {
+pub fn controller_movement(
+    mut controllers: Query<
+        (
+            &mut ExternalImpulse,
+            &LinearVelocity,
+            &Mass,
+            &Controller,
+            Option<&CCMap>,
+        ),
+        Without<Mobility>,
+    >,
+) {
+    for (mut impulse, velocity, mass, controller, cc_map) in &mut controllers {
+        // knockbacks and the like drive the velocity themselves
+        if cc_map.is_some_and(|cc_map| cc_map.is_displaced()) {
+            continue
+        }
         let mass = mass.0;
 
         let strength = 0.4;
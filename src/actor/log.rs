@@ -62,6 +62,7 @@ pub struct LogHit {
     pub ability: Ability,
     pub change: i32,
     pub mitigated: u32,
+    pub is_crit: bool,
     pub log_type: LogType,
     pub log_direction: LogSide,
 }
@@ -76,6 +77,7 @@ impl LogHit {
             ability: event.ability,
             change: event.change,
             mitigated: event.mitigated,
+            is_crit: event.is_crit,
             log_type,
             log_direction,
         }
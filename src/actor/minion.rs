@@ -7,7 +7,7 @@ use oxidized_navigation::{
 };
 */
 
-use crate::{actor::controller::Controller, prelude::*};
+use crate::{actor::controller::Controller, prelude::*, shield::Shields};
 
 pub struct MinionPlugin;
 impl Plugin for MinionPlugin {
@@ -190,13 +190,17 @@ pub fn spawn_minion(mut commands: Commands, mut spawns: EventReader<SpawnMinionE
             .add_child(minion_collider)
             .insert({
                 let mut attrs = Attributes::default();
-                attrs.set_base(Stat::Health, 50.0).set_base(Stat::Speed, 3.0);
+                attrs
+                    .set(Stat::Health, 50.0)
+                    .set_base(Stat::HealthMax, 50.0)
+                    .set_base(Stat::Speed, 3.0);
                 attrs
             })
             .insert((
                 ActorType::Minion,
                 ActorState::Alive,
                 TEAM_1,
+                Shields::default(),
                 //CCMap::default(),
                 //BuffMap::default(),
             ));
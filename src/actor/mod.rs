@@ -11,6 +11,7 @@ use crate::{
         log::{DamageSum, LogHit, LogSide, LogType},
         minion::MinionPlugin,
         player::*,
+        rank::{RankPlugin, XpEvent},
     },
     crowd_control::CCMap,
     prelude::*,
@@ -35,7 +36,13 @@ impl Plugin for ActorPlugin {
         app.add_event::<KillEvent>();
 
         //Plugins
-        app.add_plugins((MinionPlugin, ControllerPlugin, PlayerPlugin, CastPlugin));
+        app.add_plugins((
+            MinionPlugin,
+            ControllerPlugin,
+            PlayerPlugin,
+            CastPlugin,
+            RankPlugin,
+        ));
 
         //Systems
         // Process transforms always after inputs, and translations after rotations
@@ -98,6 +105,14 @@ pub fn player_swivel(mut players: Query<(&mut Transform, &PlayerInput, &CCMap, &
         if cc_map.is_stunned() || !state.is_alive() {
             continue
         }
+        if cc_map.is_feared() {
+            // face the way we are running
+            let direction = cc_map.fear_direction(player_transform.translation);
+            if direction != Vec3::ZERO {
+                player_transform.look_to(direction, Vec3::Y);
+            }
+            continue
+        }
         player_transform.rotation = Quat::from_axis_angle(Vec3::Y, inputs.yaw as f32).into();
     }
 }
@@ -109,16 +124,23 @@ pub fn player_movement(
         &PlayerInput,
         &CCMap,
         &ActorState,
+        &GlobalTransform,
     )>,
 ) {
-    for (attributes, mut controller, player_input, cc_map, state) in query.iter_mut() {
-        if cc_map.is_rooted() || cc_map.is_stunned() || !state.is_alive() {
+    for (attributes, mut controller, player_input, cc_map, state, transform) in query.iter_mut() {
+        if cc_map.is_rooted() || cc_map.is_stunned() || cc_map.is_displaced() || !state.is_alive() {
             controller.direction = Vec3::ZERO;
             // need to set to zero otherwise once stunned you 'skate' in that direction
             continue
         }
 
         let speed = attributes.get(Stat::Speed);
+        if cc_map.is_feared() {
+            // fear ignores input and runs away from whoever applied it
+            controller.direction = cc_map.fear_direction(transform.translation());
+            controller.max_speed = speed;
+            continue
+        }
         let mut direction = Vec3::new(0.0, 0.0, 0.0);
         if player_input.left() {
             direction.x += -1.;
@@ -146,6 +168,7 @@ fn give_kill_credit(
     mut victors: Query<(&mut Attributes, &ActorType)>,
     mut scoreboard: ResMut<Scoreboard>,
     mut kill_events: EventWriter<KillEvent>,
+    mut xp_events: EventWriter<XpEvent>,
 ) {
     const TIME_FOR_KILL_CREDIT: u64 = 30;
     for (bounty, state, log, damned) in changed_states.iter() {
@@ -167,8 +190,10 @@ fn give_kill_credit(
             if let Some(bounty) = bounty {
                 let gold = attributes.get_mut(Stat::Gold);
                 *gold += bounty.gold;
-                let xp = attributes.get_mut(Stat::Xp);
-                *xp += bounty.xp;
+                xp_events.send(XpEvent {
+                    entity: *awardee,
+                    amount: bounty.xp,
+                });
             }
 
             if let ActorType::Player(killer) = awardee_actor {
@@ -12,6 +12,22 @@ use crate::{
 #[derive(Resource)]
 pub struct MouseSensitivity(pub f32);
 
+/// Keys read into the ability slots of [`PlayerInput`], and the one held to rank them up instead.
+#[derive(Resource)]
+pub struct KeyBindings {
+    pub abilities: [KeyCode; 4],
+    pub level_up: KeyCode,
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        Self {
+            abilities: [KeyCode::Digit1, KeyCode::Digit2, KeyCode::Digit3, KeyCode::Digit4],
+            level_up: KeyCode::ControlLeft,
+        }
+    }
+}
+
 #[derive(SystemSet, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct InputCollectionSet;
 
@@ -22,6 +38,7 @@ impl Plugin for InputPlugin {
 
         app.insert_resource(PlayerInput::default());
         app.insert_resource(MouseSensitivity(1.0));
+        app.init_resource::<KeyBindings>();
 
         app.configure_sets(PreUpdate, InputCollectionSet.in_set(InGameSet::Pre));
 
@@ -110,6 +127,7 @@ pub fn player_mouse_input(
 pub fn player_keys_input(
     keyboard_input: Res<ButtonInput<KeyCode>>,
     mouse_input: Res<ButtonInput<MouseButton>>,
+    bindings: Res<KeyBindings>,
     mut player_input: ResMut<PlayerInput>,
 ) {
     player_input.set_forward(keyboard_input.pressed(KeyCode::KeyW) || keyboard_input.pressed(KeyCode::ArrowUp));
@@ -117,10 +135,12 @@ pub fn player_keys_input(
     player_input.set_back(keyboard_input.pressed(KeyCode::KeyS) || keyboard_input.pressed(KeyCode::ArrowDown));
     player_input.set_right(keyboard_input.pressed(KeyCode::KeyD) || keyboard_input.pressed(KeyCode::ArrowRight));
 
-    player_input.set_ability1(keyboard_input.pressed(KeyCode::Digit1));
-    player_input.set_ability2(keyboard_input.pressed(KeyCode::Digit2));
-    player_input.set_ability3(keyboard_input.pressed(KeyCode::Digit3));
-    player_input.set_ability4(keyboard_input.pressed(KeyCode::Digit4));
+    let [ability1, ability2, ability3, ability4] = bindings.abilities;
+    player_input.set_ability1(keyboard_input.pressed(ability1));
+    player_input.set_ability2(keyboard_input.pressed(ability2));
+    player_input.set_ability3(keyboard_input.pressed(ability3));
+    player_input.set_ability4(keyboard_input.pressed(ability4));
+    player_input.set_level_up(keyboard_input.pressed(bindings.level_up));
     player_input.set_left_click(mouse_input.pressed(MouseButton::Left));
     player_input.set_right_click(mouse_input.pressed(MouseButton::Right));
 }
@@ -202,6 +222,12 @@ impl PlayerInput {
             PlayerInputKeys::ABILITY_4,
         ]
     }
+    pub fn set_level_up(&mut self, pressed: bool) {
+        self.binary_inputs.set(PlayerInputKeys::LEVEL_UP, pressed);
+    }
+    pub fn level_up(&self) -> bool {
+        self.pressed(PlayerInputKeys::LEVEL_UP)
+    }
     pub fn set_left_click(&mut self, clicked: bool) {
         self.binary_inputs.set(PlayerInputKeys::LEFT_CLICK, clicked);
     }
@@ -235,6 +261,8 @@ bitflags::bitflags! {
 
         const LEFT_CLICK = 1 << 9;
         const RIGHT_CLICK = 1 << 10;
+
+        const LEVEL_UP = 1 << 11;
     }
 }
 
@@ -270,6 +298,11 @@ impl PlayerInputKeys {
             false => "-",
         };
 
+        keys += match self.contains(Self::LEVEL_UP) {
+            true => "+",
+            false => "-",
+        };
+
         if self.contains(Self::ABILITY_1) {
             keys += "1";
         };
@@ -15,9 +15,12 @@ use crate::{
     },
     buff::BuffMap,
     camera::Spectatable,
-    classes::warrior::Warrior,
+    classes::{warrior::Warrior, Class},
     crowd_control::CCMap,
+    over_time::OverTimeEffects,
+    passive::Passives,
     prelude::*,
+    shield::Shields,
     ui::{
         hud::Trackable,
         store::{StoreBuffer, StoreHistory},
@@ -99,7 +102,10 @@ pub fn init_player(
                 Inventory::default(),
                 StoreHistory::default(),
                 StoreBuffer::default(),
+                Passives::default(),
+                // Warriors are berserkers
                 Warrior,
+                Class::Berserker,
             ))
             .insert({
                 let mut attrs = Attributes::default();
@@ -128,6 +134,8 @@ pub fn init_player(
                 CooldownMap::default(),
                 CCMap::default(),
                 BuffMap::default(),
+                Shields::default(),
+                OverTimeEffects::default(),
                 Spectatable,
                 Casting::default(),
                 PlayerInput::default(),
@@ -1,6 +1,47 @@
 use bevy::{prelude::*, utils::HashMap};
 
-use crate::ability::Ability;
+use crate::{
+    ability::Ability,
+    actor::{
+        cast::{AbilitySlots, Slot},
+        player::input::{PlayerInputKeys, PlayerInputQuery},
+    },
+    prelude::*,
+};
+
+pub const MAX_LEVEL: u8 = 20;
+
+pub struct RankPlugin;
+impl Plugin for RankPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<AbilityRanks>();
+        app.add_event::<XpEvent>();
+        app.add_event::<LevelUpEvent>();
+
+        app.add_systems(Update, gain_xp.in_set(InGameSet::Update));
+        app.add_systems(FixedUpdate, spend_ability_points.in_set(InGameSet::Update));
+    }
+}
+
+/// Total xp needed to reach a level, each level costs 50 more than the last.
+pub fn xp_for_level(level: u8) -> f32 {
+    (1..level).fold(0.0, |total, level| {
+        total + 200.0 + 50.0 * (level - 1) as f32
+    })
+}
+
+/// Xp granted to an actor, levels are only checked when some comes in.
+#[derive(Event)]
+pub struct XpEvent {
+    pub entity: Entity,
+    pub amount: f32,
+}
+
+#[derive(Event)]
+pub struct LevelUpEvent {
+    pub entity: Entity,
+    pub level: u8,
+}
 
 #[derive(Reflect, Debug, Clone)]
 pub struct Rank {
@@ -14,8 +55,89 @@ impl Default for Rank {
     }
 }
 
-#[derive(Component, Reflect, Default, Debug, Clone)]
+#[derive(Component, Reflect, Debug, Clone)]
 #[reflect]
 pub struct AbilityRanks {
     pub map: HashMap<Ability, Rank>,
+    /// Unspent points, one is gained per level.
+    pub points: u8,
+}
+
+impl Default for AbilityRanks {
+    fn default() -> Self {
+        // level 1 comes with a point to spend
+        Self {
+            map: HashMap::new(),
+            points: 1,
+        }
+    }
+}
+
+impl AbilityRanks {
+    /// Current rank of the ability, 0 until a point is spent on it.
+    pub fn get(&self, ability: &Ability) -> u8 {
+        self.map.get(ability).map(|rank| rank.current).unwrap_or_default()
+    }
+
+    /// Basic attacks are always known, everything else needs a point first.
+    pub fn is_learned(&self, ability: &Ability) -> bool {
+        *ability == Ability::BasicAttack || self.get(ability) > 0
+    }
+
+    pub fn can_rank_up(&self, ability: &Ability) -> bool {
+        let rank = self.map.get(ability).cloned().unwrap_or_default();
+        self.points > 0 && rank.current < rank.max
+    }
+
+    /// Spend a point on the ability, the first one learns it. Returns false if there was nothing to spend or it is maxed.
+    pub fn rank_up(&mut self, ability: Ability) -> bool {
+        if !self.can_rank_up(&ability) {
+            return false
+        }
+        self.map.entry(ability).or_default().current += 1;
+        self.points -= 1;
+        true
+    }
+}
+
+fn gain_xp(
+    mut xp_events: EventReader<XpEvent>,
+    mut actors: Query<(&mut Attributes, Option<&mut AbilityRanks>)>,
+    mut level_events: EventWriter<LevelUpEvent>,
+) {
+    for event in xp_events.read() {
+        let entity = event.entity;
+        let Ok((mut attributes, ranks)) = actors.get_mut(entity) else { continue };
+        let xp = attributes.get(Stat::Xp) + event.amount;
+        attributes.set(Stat::Xp, xp);
+        // only actors that can rank abilities level up
+        let Some(mut ranks) = ranks else { continue };
+        let old_level = attributes.get(Stat::Level) as u8;
+        let mut level = old_level;
+        while level < MAX_LEVEL && xp >= xp_for_level(level + 1) {
+            level += 1;
+            ranks.points += 1;
+            level_events.send(LevelUpEvent { entity, level });
+        }
+        if level != old_level {
+            attributes.set(Stat::Level, level as f32);
+        }
+    }
+}
+
+// Hold the level up key and press an ability slot to spend a point on it
+fn spend_ability_points(mut actors: Query<(&mut AbilityRanks, &AbilitySlots, PlayerInputQuery)>) {
+    for (mut ranks, ability_slots, input) in &mut actors {
+        if !input.pressed(PlayerInputKeys::LEVEL_UP) {
+            continue
+        }
+        for (index, ability_key) in input.slots().iter().enumerate() {
+            if !input.just_pressed(*ability_key) {
+                continue
+            }
+            let Some(slot) = Slot::from_index(index) else { continue };
+            let Some(ability) = ability_slots.get(slot) else { continue };
+            ranks.rank_up(ability);
+        }
+    }
 }
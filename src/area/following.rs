@@ -0,0 +1,16 @@
+use crate::prelude::*;
+
+/// Keeps an area on top of another entity, like a stage that rides along with the caster.
+#[derive(Component, Debug)]
+pub struct Following(pub Entity);
+
+pub fn follow_targets(
+    mut followers: Query<(&Following, &mut Transform)>,
+    targets: Query<&GlobalTransform, Without<Following>>,
+) {
+    for (following, mut transform) in &mut followers {
+        // stays where it was last if the target is gone
+        let Ok(target) = targets.get(following.0) else { continue };
+        transform.translation = target.translation();
+    }
+}
@@ -0,0 +1,65 @@
+use crate::{
+    ability::{
+        builder::{AbilityStage, CollisionMask, Trigger},
+        db::AbilityRegistry,
+        scale_tags, Ability, Tags, TargetsInArea,
+    },
+    actor::cast::Caster,
+    area::timeline::{AreaTimeline, CastStage},
+    prelude::*,
+};
+
+/// Spawns the ability's Collision stage where the area first touches something in the mask.
+#[derive(Component, Debug)]
+pub struct ImpactStage {
+    pub mask: CollisionMask,
+    pub rank: u8,
+}
+
+// Runs before despawn_after_max_hits so projectiles that die on their first hit still explode
+pub fn trigger_impact_stages(
+    mut commands: Commands,
+    areas: Query<(
+        Entity,
+        &ImpactStage,
+        &TargetsInArea,
+        &Team,
+        &Caster,
+        &Ability,
+        &GlobalTransform,
+        Option<&AreaTimeline>,
+    )>,
+    targets: Query<Option<&Team>, Without<Sensor>>,
+    casters: Query<&Attributes>,
+    registry: Res<AbilityRegistry>,
+) {
+    for (entity, impact, targets_in_area, team, caster, ability, transform, timeline) in &areas {
+        if timeline.is_some_and(|timeline| timeline.stage != CastStage::Firing) {
+            continue
+        }
+        let hit = targets_in_area.list.iter().any(|target| match targets.get(*target) {
+            _ if *target == caster.0 => false,
+            Ok(Some(target_team)) if target_team.0 == team.0 => impact.mask.allies,
+            Ok(Some(_)) => impact.mask.enemies,
+            // walls are the only thing abilities collide with that isnt on a team
+            Ok(None) => impact.mask.walls,
+            Err(_) => false,
+        });
+        if !hit {
+            continue
+        }
+        commands.entity(entity).remove::<ImpactStage>();
+
+        let definition = registry.get(ability);
+        let info = registry.info(ability, impact.rank);
+        let Some(on_collision) = &definition.on_collision else { continue };
+        let Some(AbilityStage::DeployArea(deploy)) = info.stages.get(&Trigger::Collision) else { continue };
+        let tags = match casters.get(caster.0) {
+            Ok(attributes) => scale_tags(&deploy.tags, attributes),
+            Err(_) => deploy.tags.clone(),
+        };
+        commands
+            .spawn(definition.follow_up(deploy, on_collision.duration, transform.translation()))
+            .insert((*team, Caster(caster.0), Tags(tags)));
+    }
+}
@@ -0,0 +1,55 @@
+use crate::{assets::MaterialPresets, prelude::*};
+
+// How high a lob peaks compared to how far it travels
+const LOB_HEIGHT_RATIO: f32 = 0.3;
+
+/// Projectile lobbed at an area waiting in Windup, it lands as the area starts Firing.
+#[derive(Component, Debug)]
+pub struct Lob {
+    pub start: Vec3,
+    pub end: Vec3,
+    pub height: f32,
+    pub timer: Timer,
+}
+
+impl Lob {
+    pub fn new(start: Vec3, end: Vec3, flight: f32) -> Self {
+        Self {
+            start,
+            end,
+            height: start.distance(end) * LOB_HEIGHT_RATIO,
+            timer: Timer::from_seconds(flight, TimerMode::Once),
+        }
+    }
+
+    /// Seconds in the air to cover the ground between start and end at this speed.
+    pub fn flight_time(start: Vec3, end: Vec3, speed: f32) -> f32 {
+        Vec2::new(end.x - start.x, end.z - start.z).length() / speed.max(f32::EPSILON)
+    }
+}
+
+pub fn add_lob_mesh(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    presets: Res<MaterialPresets>,
+    lobs: Query<Entity, Added<Lob>>,
+) {
+    for entity in &lobs {
+        let mut lob = commands.entity(entity);
+        lob.insert(meshes.add(Sphere::new(0.3)));
+        if let Some(material) = presets.0.get("red") {
+            lob.insert(material.clone());
+        }
+    }
+}
+
+pub fn move_lobs(mut commands: Commands, time: Res<Time>, mut lobs: Query<(Entity, &mut Lob, &mut Transform)>) {
+    for (entity, mut lob, mut transform) in &mut lobs {
+        lob.timer.tick(time.delta());
+        let t = lob.timer.fraction();
+        transform.translation = lob.start.lerp(lob.end, t) + Vec3::Y * 4.0 * lob.height * t * (1.0 - t);
+        if lob.timer.finished() {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
@@ -1,7 +1,10 @@
 use std::collections::HashMap;
 
 use bevy::prelude::*;
+use following::follow_targets;
 use homing::track_homing;
+use impact::trigger_impact_stages;
+use lob::{add_lob_mesh, move_lobs};
 
 use crate::{
     ability::Ability,
@@ -14,6 +17,11 @@ impl Plugin for AreaPlugin {
         app.add_event::<HealthChangeEvent>();
         app.add_event::<BuffEvent>();
         app.add_event::<CCEvent>();
+        app.add_event::<ShieldEvent>();
+        app.add_event::<OverTimeEvent>();
+        app.add_event::<CleanseEvent>();
+        app.add_event::<PurifyEvent>();
+        app.add_event::<ImmunityEvent>();
         app.add_event::<AreaOverlapEvent>();
 
         app.add_systems(PreUpdate, catch_collisions);
@@ -22,6 +30,9 @@ impl Plugin for AreaPlugin {
             (
                 tick_hit_timers,
                 track_homing,
+                follow_targets,
+                move_lobs,
+                add_lob_mesh,
                 add_health_bar_detect_colliders,
                 focus_objective_health,
                 tick_timeline.before(area_queue_targets),
@@ -33,6 +44,7 @@ impl Plugin for AreaPlugin {
                 filter_targets,
                 area_queue_targets,
                 area_apply_tags,
+                trigger_impact_stages,
                 despawn_after_max_hits,
             )
                 .chain(),
@@ -51,7 +63,10 @@ pub enum AbilityBehavior {
     OnHit,
 }
 
+pub mod following;
 pub mod homing;
+pub mod impact;
+pub mod lob;
 pub mod non_damaging;
 pub mod queue;
 pub mod timeline;
@@ -10,15 +10,17 @@ use rand::Rng;
 use crate::{
     ability::{
         ticks::{PausesWhenEmpty, TickBehavior, TickKind},
-        Ability, DamageType, MaxTargetsHit, TagInfo, Tags, TargetFilter, TargetSelection, TargetsHittable,
+        Ability, CanCrit, DamageType, MaxTargetsHit, TagInfo, Tags, TargetFilter, TargetSelection, TargetsHittable,
         TargetsInArea, UniqueTargetsHit,
     },
     actor::cast::{AbilityExtras, AbilityFireEvent, Caster},
     area::{AreaTimeline, CastStage},
-    buff::{BuffInfo, BuffTargets},
-    crowd_control::CCInfo,
+    buff::{BuffInfo, BuffSource, BuffTargets},
+    crowd_control::{CCInfo, CCKind, CCSelection, Displacement},
+    over_time::{OverTimeInfo, OverTimeKind},
     prelude::ActorState,
     session::team::Team,
+    shield::ShieldInfo,
     stats::{Attributes, Stat},
 };
 
@@ -44,12 +46,20 @@ pub fn area_apply_tags(
         Option<&Caster>,
         Option<&DamageType>,
         Option<&Ability>,
+        Option<&CanCrit>,
+        Option<&TargetFilter>,
+        &GlobalTransform,
     )>,
     mut targets_query: Query<(&Team, &ActorState)>,
     mut casters: Query<&mut Attributes>,
     mut health_events: EventWriter<HealthChangeEvent>,
     mut buff_events: EventWriter<BuffEvent>,
     mut cc_events: EventWriter<CCEvent>,
+    mut shield_events: EventWriter<ShieldEvent>,
+    mut over_time_events: EventWriter<OverTimeEvent>,
+    mut cleanse_events: EventWriter<CleanseEvent>,
+    mut purify_events: EventWriter<PurifyEvent>,
+    mut immunity_events: EventWriter<ImmunityEvent>,
     mut cast_homing_events: EventWriter<AbilityFireEvent>,
 ) {
     'area_loop: for (
@@ -63,10 +73,20 @@ pub fn area_apply_tags(
         caster,
         damage_type,
         ability,
+        can_crit,
+        target_filter,
+        sensor_transform,
     ) in &mut sensor_query
     {
         let mut hit_targets: Vec<Entity> = Vec::new();
+        // only areas without a target limit can hit a crowd
+        let is_area = max_targets_hit.is_none() && target_filter.is_none();
         let ability = ability.unwrap_or(&Ability::BasicAttack);
+        // areas nobody cast belong to the map
+        let buff_source = match caster {
+            Some(_) => BuffSource::Ability(*ability),
+            None => BuffSource::Objective(sensor_entity),
+        };
         let damage_type = damage_type.unwrap_or(&DamageType::True);
 
         for target_entity in targets_hittable.list.iter() {
@@ -89,9 +109,20 @@ pub fn area_apply_tags(
             let on_same_team = sensor_team.0 == target_team.0;
             let mut hit_the_target = false;
 
+            // one roll per target hit, so every damage tag on the area agrees
+            let mut is_crit = false;
+            let mut crit_multiplier = 1.0;
+            if let (Some(_), Ok(attrs)) = (can_crit, casters.get(caster)) {
+                let crit_chance = attrs.get(Stat::CritChance) / 100.0;
+                if rand::thread_rng().gen::<f32>() < crit_chance {
+                    is_crit = true;
+                    crit_multiplier = attrs.get(Stat::CritDamage) / 100.0;
+                }
+            }
+
             for taginfo in tags.iter() {
                 match (taginfo, on_same_team) {
-                    (TagInfo::Heal(amount), true) => {
+                    (TagInfo::Heal { base: amount, .. }, true) => {
                         hit_the_target = true;
                         let health_change = HealthChangeEvent {
                             amount: *amount,
@@ -100,31 +131,89 @@ pub fn area_apply_tags(
                             attacker: caster,
                             defender: *target_entity,
                             sensor: sensor_entity,
+                            is_crit: false,
+                            is_area,
                             when: Instant::now(),
                         };
                         health_events.send(health_change);
                     }
-                    (TagInfo::Damage(amount), false) => {
+                    (TagInfo::Damage { base: amount, .. }, false) => {
                         hit_the_target = true;
                         let health_change = HealthChangeEvent {
-                            amount: -amount,
+                            amount: -amount * crit_multiplier,
                             damage_type: damage_type.clone(),
                             ability: ability.clone(),
                             attacker: caster,
                             defender: *target_entity,
                             sensor: sensor_entity,
+                            is_crit,
+                            is_area,
                             when: Instant::now(),
                         };
                         health_events.send(health_change);
                     }
+                    (
+                        TagInfo::Shield {
+                            amount,
+                            duration,
+                            blocks,
+                            ..
+                        },
+                        true,
+                    ) => {
+                        hit_the_target = true;
+                        shield_events.send(ShieldEvent {
+                            target: *target_entity,
+                            caster,
+                            info: ShieldInfo {
+                                amount: *amount,
+                                duration: *duration,
+                                blocks: *blocks,
+                            },
+                        });
+                    }
+                    (TagInfo::Cleanse(ccs), true) => {
+                        hit_the_target = true;
+                        cleanse_events.send(CleanseEvent {
+                            target: *target_entity,
+                            ccs: ccs.clone(),
+                        });
+                    }
+                    (TagInfo::Purify, true) => {
+                        hit_the_target = true;
+                        purify_events.send(PurifyEvent { target: *target_entity });
+                    }
+                    (TagInfo::Immunity { ccs, duration }, true) => {
+                        hit_the_target = true;
+                        immunity_events.send(ImmunityEvent {
+                            target: *target_entity,
+                            ccs: ccs.clone(),
+                            duration: *duration,
+                        });
+                    }
+                    (TagInfo::HealOverTime(info), true) | (TagInfo::DamageOverTime(info), false) => {
+                        hit_the_target = true;
+                        let kind = match taginfo {
+                            TagInfo::HealOverTime(_) => OverTimeKind::Heal,
+                            _ => OverTimeKind::Damage,
+                        };
+                        over_time_events.send(OverTimeEvent {
+                            info: info.clone(),
+                            kind,
+                            damage_type: *damage_type,
+                            ability: *ability,
+                            caster,
+                            target: *target_entity,
+                            sensor: sensor_entity,
+                        });
+                    }
                     (TagInfo::Buff(buffinfo), on_same_team) => {
                         let buffing_ally = (buffinfo.bufftargets == BuffTargets::Allies) && on_same_team;
                         let debuffing_enemy = (buffinfo.bufftargets == BuffTargets::Enemies) && !on_same_team;
                         let buffing_anyone = buffinfo.bufftargets == BuffTargets::All;
                         let buff_to_send = BuffEvent {
                             info: buffinfo.clone(),
-                            ability: *ability,
-                            buff_originator: sensor_entity, // TODO can be a specific ability, or an item
+                            source: buff_source,
                             caster,
                             target: *target_entity,
                         };
@@ -139,6 +228,33 @@ pub fn area_apply_tags(
                         cc_events.send(CCEvent {
                             target_entity: *target_entity,
                             ccinfo: ccinfo.clone(),
+                            source: sensor_transform.translation(),
+                            displacement: None,
+                            slow: None,
+                        });
+                    }
+                    (TagInfo::Slow { percent, duration }, false) => {
+                        hit_the_target = true;
+                        cc_events.send(CCEvent {
+                            target_entity: *target_entity,
+                            ccinfo: CCInfo {
+                                cckind: CCKind::Slow,
+                                duration: *duration,
+                            },
+                            source: sensor_transform.translation(),
+                            displacement: None,
+                            slow: Some(*percent),
+                        });
+                    }
+                    (TagInfo::Knockback { .. } | TagInfo::Pull { .. } | TagInfo::Knockup { .. }, false) => {
+                        let Some((displacement, ccinfo)) = taginfo.displacement() else { continue };
+                        hit_the_target = true;
+                        cc_events.send(CCEvent {
+                            target_entity: *target_entity,
+                            ccinfo,
+                            source: sensor_transform.translation(),
+                            displacement: Some(displacement),
+                            slow: None,
                         });
                     }
                     (TagInfo::Homing(homing_ability), _) => {
@@ -379,6 +495,9 @@ pub struct HealthChangeEvent {
     pub attacker: Entity,
     pub defender: Entity,
     pub sensor: Entity,
+    pub is_crit: bool,
+    /// Came from an area that can hit more than one target.
+    pub is_area: bool,
     pub when: Instant,
 }
 
@@ -386,15 +505,55 @@ pub struct HealthChangeEvent {
 pub struct BuffEvent {
     pub info: BuffInfo,
     pub target: Entity,
-    pub buff_originator: Entity,
+    pub source: BuffSource,
     pub caster: Entity,
+}
+
+#[derive(Event)]
+pub struct OverTimeEvent {
+    pub info: OverTimeInfo,
+    pub kind: OverTimeKind,
+    pub damage_type: DamageType,
     pub ability: Ability,
+    pub caster: Entity,
+    pub target: Entity,
+    pub sensor: Entity,
+}
+
+#[derive(Event)]
+pub struct CleanseEvent {
+    pub target: Entity,
+    pub ccs: CCSelection,
+}
+
+#[derive(Event)]
+pub struct PurifyEvent {
+    pub target: Entity,
+}
+
+#[derive(Event)]
+pub struct ImmunityEvent {
+    pub target: Entity,
+    pub ccs: CCSelection,
+    pub duration: f32,
+}
+
+#[derive(Event)]
+pub struct ShieldEvent {
+    pub target: Entity,
+    pub caster: Entity,
+    pub info: ShieldInfo,
 }
 
 #[derive(Event)]
 pub struct CCEvent {
     pub target_entity: Entity,
     pub ccinfo: CCInfo,
+    /// Where the CC came from, fear runs away from here.
+    pub source: Vec3,
+    pub displacement: Option<Displacement>,
+    /// Percent of speed taken, for slows.
+    pub slow: Option<f32>,
 }
 
 #[derive(PartialEq, Eq)]
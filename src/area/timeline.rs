@@ -1,6 +1,7 @@
 use std::{collections::HashMap, time::Duration};
 
 use bevy::prelude::*;
+use serde::Deserialize;
 
 pub(super) fn tick_timeline(
     mut commands: Commands,
@@ -47,7 +48,7 @@ impl AreaTimeline {
     }
 }
 
-#[derive(Clone, Debug, Reflect, PartialEq, Eq, Hash)]
+#[derive(Clone, Debug, Reflect, PartialEq, Eq, Hash, Deserialize)]
 pub enum CastStage {
     Input,  // Player hit key on instacast, or left-clicked normal cast
     Casted, // animation + sound queues went off without getting cc'd
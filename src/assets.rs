@@ -3,7 +3,7 @@
 use bevy::utils::HashMap;
 use bevy_asset_loader::prelude::*;
 
-use crate::{prelude::*, GameState};
+use crate::{ability::definition::AbilityDefinition, prelude::*, GameState};
 
 #[derive(AssetCollection, Resource)]
 pub struct Icons {
@@ -85,6 +85,12 @@ pub struct Audio {
     pub blip: Handle<AudioSource>,
 }
 
+#[derive(AssetCollection, Resource)]
+pub struct AbilityDefinitions {
+    #[asset(path = "abilities", collection(typed))]
+    pub definitions: Vec<Handle<AbilityDefinition>>,
+}
+
 #[derive(Resource)]
 pub struct MaterialPresets(pub HashMap<String, Handle<StandardMaterial>>);
 
@@ -100,7 +106,8 @@ impl Plugin for GameAssetPlugin {
                 .load_collection::<Scenes>()
                 .load_collection::<Models>()
                 .load_collection::<Audio>()
-                .load_collection::<Items>(),
+                .load_collection::<Items>()
+                .load_collection::<AbilityDefinitions>(),
         );
 
         app.add_systems(Startup, load_presets);
@@ -1,11 +1,15 @@
-use std::{collections::HashMap, time::Duration};
+use std::{collections::HashMap, fmt::Display, time::Duration};
 
 use bevy::prelude::*;
+use serde::Deserialize;
 
 use crate::{
-    area::queue::BuffEvent,
+    ability::Ability,
+    area::queue::{BuffEvent, PurifyEvent},
+    classes::Class,
+    item::Item,
     session::director::InGameSet,
-    stats::{AttributeTag, Attributes, Stat},
+    stats::{AttributeTag, Attributes, ModifierSource},
 };
 pub struct BuffPlugin;
 impl Plugin for BuffPlugin {
@@ -13,20 +17,48 @@ impl Plugin for BuffPlugin {
         app.add_event::<BuffAddEvent>();
         app.add_event::<BuffStackEvent>();
 
-        app.add_systems(Update, (apply_buffs, tick_buffs).in_set(InGameSet::Update));
+        app.add_systems(
+            Update,
+            (apply_buffs, tick_buffs, purify_debuffs).in_set(InGameSet::Update),
+        );
+    }
+}
+
+/// What gave a buff, so the same buff from different places can live side by side.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum BuffSource {
+    Ability(Ability),
+    Item(Item),
+    Passive(Class),
+    Objective(Entity), // map areas that buff whoever walks in
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct BuffId {
+    pub source: BuffSource,
+    pub caster: Entity,
+    /// Tells apart different buffs from the same source.
+    pub name: String,
+}
+
+impl Display for BuffId {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.name)
     }
 }
 
 #[derive(Event)]
 pub struct BuffStackEvent {
-    pub id: String,
+    pub id: BuffId,
     pub target: Entity,
     pub stacks: u32,
+    /// Seconds until the last stack falls off.
+    pub remaining: f32,
 }
 
 #[derive(Event)]
 pub struct BuffAddEvent {
-    pub id: String,
+    pub id: BuffId,
     pub target: Entity,
     pub info: BuffInfo,
 }
@@ -38,83 +70,166 @@ fn apply_buffs(
     mut add_events: EventWriter<BuffAddEvent>,
 ) {
     for event in buff_events.read() {
-        if let Ok((mut buffs, mut attributes)) = targets_query.get_mut(event.target) {
-            let ability = format!(
-                "{}v{}",
-                event.buff_originator.index(),
-                event.buff_originator.generation()
-            );
-            let caster = format!("{}v{}", event.caster.index(), event.caster.generation());
-
-            let buff_id = format!("{}_{}_{}", caster, ability, event.info.stat.to_string());
-            let mut added_stack = false;
-            if buffs.map.contains_key(&buff_id) {
-                let Some(applied) = buffs.map.get_mut(&buff_id) else { continue };
-                if applied.info.max_stacks > applied.stacks {
-                    applied.stacks += 1;
-                    added_stack = true;
-                }
-                applied.timer.reset(); // reset timer, TODO need individual stack timer support
-                stack_events.send(BuffStackEvent {
-                    id: buff_id,
-                    target: event.target,
-                    stacks: applied.stacks,
-                });
-            } else {
-                added_stack = true;
-                buffs.map.insert(
-                    buff_id.clone(),
-                    BuffInfoApplied {
-                        info: event.info.clone(),
-                        stacks: 1,
-                        timer: Timer::new(
-                            Duration::from_millis((event.info.duration * 1000.0) as u64),
-                            TimerMode::Once,
-                        ),
-                    },
-                );
-                add_events.send(BuffAddEvent {
-                    id: buff_id,
-                    target: event.target,
+        let Ok((mut buffs, mut attributes)) = targets_query.get_mut(event.target) else { continue };
+        let buff_id = BuffId {
+            source: event.source,
+            caster: event.caster,
+            name: event.info.name.clone(),
+        };
+        let added_stack;
+        if let Some(applied) = buffs.map.get_mut(&buff_id) {
+            let info = &event.info;
+            added_stack = applied.stacks.add(info.duration, info.max_stacks, &info.refresh);
+            stack_events.send(BuffStackEvent {
+                id: buff_id.clone(),
+                target: event.target,
+                stacks: applied.stacks.count(),
+                remaining: applied.stacks.remaining(),
+            });
+        } else {
+            added_stack = true;
+            buffs.map.insert(
+                buff_id.clone(),
+                BuffInfoApplied {
                     info: event.info.clone(),
-                });
-            }
-            if added_stack {
-                let stat = attributes.get_mut(event.info.stat.clone());
-                *stat += event.info.amount;
-            }
+                    stacks: Stacks::new(event.info.duration),
+                },
+            );
+            add_events.send(BuffAddEvent {
+                id: buff_id.clone(),
+                target: event.target,
+                info: event.info.clone(),
+            });
+        }
+        if added_stack {
+            let Some(applied) = buffs.map.get(&buff_id) else { continue };
+            set_buff_modifiers(
+                &mut attributes,
+                &buff_id,
+                &event.info,
+                applied.stacks.count(),
+            );
+            change_buff_pools(&mut attributes, &event.info, 1.0);
         }
     }
 }
 
-fn tick_buffs(time: Res<Time>, mut query: Query<(&mut BuffMap, &mut Attributes)>) {
-    for (mut buffs, mut attributes) in &mut query {
-        buffs.map.retain(|_, buff| {
-            buff.timer.tick(time.delta());
-            if buff.timer.finished() {
-                let stat = attributes.get_mut(buff.info.stat.clone());
-                *stat -= buff.stacks as f32 * buff.info.amount;
-                false
+fn tick_buffs(
+    time: Res<Time>,
+    mut query: Query<(Entity, &mut BuffMap, &mut Attributes)>,
+    mut stack_events: EventWriter<BuffStackEvent>,
+) {
+    for (target, mut buffs, mut attributes) in &mut query {
+        buffs.map.retain(|id, buff| {
+            let lost = buff.stacks.tick(time.delta(), &buff.info.falloff);
+            if lost == 0 {
+                return true
+            }
+            if buff.stacks.is_empty() {
+                attributes.remove_source(&ModifierSource::Buff(id.clone()));
             } else {
-                true
+                set_buff_modifiers(&mut attributes, id, &buff.info, buff.stacks.count());
+            }
+            change_buff_pools(&mut attributes, &buff.info, -(lost as f32));
+            stack_events.send(BuffStackEvent {
+                id: id.clone(),
+                target,
+                stacks: buff.stacks.count(),
+                remaining: buff.stacks.remaining(),
+            });
+            !buff.stacks.is_empty()
+        });
+    }
+}
+
+fn purify_debuffs(
+    mut targets_query: Query<(&mut BuffMap, &mut Attributes)>,
+    mut purify_events: EventReader<PurifyEvent>,
+    mut stack_events: EventWriter<BuffStackEvent>,
+) {
+    for event in purify_events.read() {
+        let Ok((mut buffs, mut attributes)) = targets_query.get_mut(event.target) else { continue };
+        buffs.map.retain(|id, buff| {
+            if buff.info.bufftype != BuffType::Debuff {
+                return true
             }
+            attributes.remove_source(&ModifierSource::Buff(id.clone()));
+            change_buff_pools(&mut attributes, &buff.info, -(buff.stacks.count() as f32));
+            // no stacks left lets the buff bar drop it
+            stack_events.send(BuffStackEvent {
+                id: id.clone(),
+                target: event.target,
+                stacks: 0,
+                remaining: 0.0,
+            });
+            false
         });
     }
 }
 
+// every stack lives under the one buff source so expiring takes all of them off
+fn set_buff_modifiers(attributes: &mut Attributes, id: &BuffId, info: &BuffInfo, stacks: u32) {
+    for (stat, amount) in info.stats.iter() {
+        let Some(tag) = stat.as_modifier() else { continue };
+        attributes.set_modifier(
+            tag,
+            ModifierSource::Buff(id.clone()),
+            stacks as f32 * amount,
+        );
+    }
+}
+
+// pools like health have no modifier stack, so stacks coming and going change them directly
+fn change_buff_pools(attributes: &mut Attributes, info: &BuffInfo, stacks: f32) {
+    for (stat, amount) in info.stats.iter() {
+        if stat.as_modifier().is_some() {
+            continue
+        }
+        *attributes.get_mut(stat.target_stat()) += stacks * amount;
+    }
+}
+
 #[derive(Component, Default, Debug, Clone)]
 pub struct BuffMap {
-    pub map: HashMap<String, BuffInfoApplied>, // Create buff id from entity-ability/item-positive, orc2-spear-debuff aka what it comes from
+    pub map: HashMap<BuffId, BuffInfoApplied>,
 }
 
-#[derive(Default, Clone, Copy, Debug, Reflect, Eq, PartialEq)]
+impl BuffMap {
+    pub fn get(&self, id: &BuffId) -> Option<&BuffInfoApplied> {
+        self.map.get(id)
+    }
+
+    pub fn contains(&self, id: &BuffId) -> bool {
+        self.map.contains_key(id)
+    }
+
+    /// Whether any buff with this name is active, no matter who applied it.
+    pub fn has_named(&self, name: &str) -> bool {
+        self.map.keys().any(|id| id.name == name)
+    }
+
+    /// Stacks of every buff with this name added together.
+    pub fn stacks_named(&self, name: &str) -> u32 {
+        self.map
+            .iter()
+            .filter(|(id, _)| id.name == name)
+            .map(|(_, applied)| applied.stacks.count())
+            .sum()
+    }
+
+    pub fn from_source(&self, source: BuffSource) -> impl Iterator<Item = (&BuffId, &BuffInfoApplied)> {
+        self.map.iter().filter(move |(id, _)| id.source == source)
+    }
+}
+
+#[derive(Default, Clone, Copy, Debug, Reflect, Eq, PartialEq, Deserialize)]
 pub enum BuffType {
     #[default]
     Buff,
     Debuff,
 }
 
-#[derive(Default, Clone, Debug, Reflect, Eq, PartialEq)]
+#[derive(Default, Clone, Debug, Reflect, Eq, PartialEq, Deserialize)]
 pub enum BuffTargets {
     #[default]
     Allies,
@@ -122,53 +237,202 @@ pub enum BuffTargets {
     All,
 }
 
-#[derive(Clone, Debug, Reflect)]
+#[derive(Clone, Debug, Default, Reflect, Deserialize)]
 pub enum StackFalloff {
-    Individual,    // buff stacks drop one at a time,
-    All,           // buff stacks drop at the same time,
+    Individual, // buff stacks drop one at a time,
+    #[default]
+    All, // buff stacks drop at the same time,
     Multiple(u32), // varying amount of falloff, pretty niche
 }
 
-#[derive(Clone, Debug, Reflect)]
+#[derive(Clone, Debug, Default, Reflect, Deserialize)]
 pub enum StackRefresh {
     None, // adding a stack doesnt refesh any,
-    All,  // adding a stack refreshes all
+    #[default]
+    All, // adding a stack refreshes all
 }
 
-#[derive(Clone, Debug, Reflect)]
+/// One timer per stack, oldest first, so stacks can run out on their own schedule.
+#[derive(Clone, Debug, Default)]
+pub struct Stacks {
+    timers: Vec<Timer>,
+}
+
+impl Stacks {
+    pub fn new(duration: f32) -> Self {
+        Self {
+            timers: vec![stack_timer(duration)],
+        }
+    }
+
+    pub fn count(&self) -> u32 {
+        self.timers.len() as u32
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.timers.is_empty()
+    }
+
+    /// Seconds until the longest lasting stack runs out.
+    pub fn remaining(&self) -> f32 {
+        self.timers
+            .iter()
+            .map(|timer| timer.remaining_secs())
+            .fold(0.0, f32::max)
+    }
+
+    /// Add a stack, returns false if it was already at max stacks.
+    pub fn add(&mut self, duration: f32, max_stacks: u32, refresh: &StackRefresh) -> bool {
+        if let StackRefresh::All = refresh {
+            for timer in self.timers.iter_mut() {
+                timer.reset();
+            }
+        }
+        if self.count() < max_stacks {
+            self.timers.push(stack_timer(duration));
+            return true
+        }
+        // without a refresh a full buff is left to run out
+        false
+    }
+
+    /// Tick every stack, returns how many fell off.
+    pub fn tick(&mut self, delta: Duration, falloff: &StackFalloff) -> u32 {
+        for timer in self.timers.iter_mut() {
+            timer.tick(delta);
+        }
+        if !self.timers.iter().any(|timer| timer.finished()) {
+            return 0
+        }
+        let lost = match *falloff {
+            StackFalloff::Individual => 1,
+            StackFalloff::All => self.timers.len(),
+            StackFalloff::Multiple(amount) => (amount as usize).min(self.timers.len()),
+        };
+        // older stacks always finish first
+        self.timers.drain(..lost);
+        // the stacks left behind start over instead of falling off with the rest
+        for timer in self.timers.iter_mut().filter(|timer| timer.finished()) {
+            timer.reset();
+        }
+        lost as u32
+    }
+}
+
+fn stack_timer(duration: f32) -> Timer {
+    Timer::new(
+        Duration::from_millis((duration * 1000.0) as u64),
+        TimerMode::Once,
+    )
+}
+
+#[derive(Clone, Debug, Reflect, Deserialize)]
 pub struct BuffInfo {
+    /// No default, buffs from the same source and caster are told apart by name.
     pub name: String,
-    pub stat: AttributeTag,
-    pub amount: f32,
+    /// Every stat the buff changes and by how much per stack.
+    #[serde(default)]
+    pub stats: Vec<(AttributeTag, f32)>,
+    #[serde(default = "default_max_stacks")]
     pub max_stacks: u32,
+    #[serde(default = "default_duration")]
     pub duration: f32,
+    #[serde(default)]
     pub falloff: StackFalloff,
+    #[serde(default)]
     pub refresh: StackRefresh,
+    #[serde(default = "default_bufftargets")]
     pub bufftargets: BuffTargets,
+    #[serde(default = "default_bufftype")]
     pub bufftype: BuffType,
+    #[serde(skip)]
     pub image: UiImage,
 }
 
-impl Default for BuffInfo {
-    fn default() -> Self {
+impl BuffInfo {
+    /// Everything but the name left at its default, fill in the rest with `..BuffInfo::new(name)`.
+    pub fn new(name: impl Into<String>) -> Self {
         Self {
-            name: "Blessing of Might".to_string(),
-            stat: AttributeTag::Stat(Stat::Health),
-            amount: 1.0,
-            max_stacks: 1,
-            duration: 5.0,
-            falloff: StackFalloff::All,
-            refresh: StackRefresh::All,
-            bufftargets: BuffTargets::Enemies,
-            bufftype: BuffType::Debuff,
+            name: name.into(),
+            stats: Vec::new(),
+            max_stacks: default_max_stacks(),
+            duration: default_duration(),
+            falloff: StackFalloff::default(),
+            refresh: StackRefresh::default(),
+            bufftargets: default_bufftargets(),
+            bufftype: default_bufftype(),
             image: UiImage::default(),
         }
     }
 }
 
+fn default_max_stacks() -> u32 {
+    1
+}
+
+fn default_duration() -> f32 {
+    5.0
+}
+
+fn default_bufftargets() -> BuffTargets {
+    BuffTargets::Enemies
+}
+
+fn default_bufftype() -> BuffType {
+    BuffType::Debuff
+}
+
 #[derive(Debug, Clone)]
 pub struct BuffInfoApplied {
     pub info: BuffInfo,
-    pub stacks: u32,
-    pub timer: Timer, // change to vec of timers for individual falloff
+    pub stacks: Stacks,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn full_stacks(falloff: &StackFalloff) -> (Stacks, u32) {
+        let mut stacks = Stacks::new(1.0);
+        for _ in 0..3 {
+            stacks.add(1.0, 4, &StackRefresh::All);
+        }
+        let lost = stacks.tick(Duration::from_secs(1), falloff);
+        (stacks, lost)
+    }
+
+    #[test]
+    fn individual_falloff_drops_one_stack() {
+        let (mut stacks, lost) = full_stacks(&StackFalloff::Individual);
+        assert_eq!(lost, 1);
+        assert_eq!(stacks.count(), 3);
+        assert_eq!(
+            stacks.tick(Duration::from_millis(500), &StackFalloff::Individual),
+            0
+        );
+        assert_eq!(
+            stacks.tick(Duration::from_millis(500), &StackFalloff::Individual),
+            1
+        );
+        assert_eq!(stacks.count(), 2);
+    }
+
+    #[test]
+    fn multiple_falloff_drops_that_many_stacks() {
+        let (mut stacks, lost) = full_stacks(&StackFalloff::Multiple(3));
+        assert_eq!(lost, 3);
+        assert_eq!(stacks.count(), 1);
+        assert_eq!(
+            stacks.tick(Duration::from_secs(1), &StackFalloff::Multiple(3)),
+            1
+        );
+        assert!(stacks.is_empty());
+    }
+
+    #[test]
+    fn all_falloff_drops_every_stack() {
+        let (stacks, lost) = full_stacks(&StackFalloff::All);
+        assert_eq!(lost, 4);
+        assert!(stacks.is_empty());
+    }
 }
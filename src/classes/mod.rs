@@ -1,10 +1,27 @@
 use bevy::prelude::*;
 
-use crate::ability::Ability;
+use crate::{
+    ability::Ability,
+    buff::BuffSource,
+    passive::{KillFilter, Passive, PassiveCondition, PassiveEffect, Passives},
+    session::director::InGameSet,
+    stats::{Attributes, ModifierSource, Stat},
+};
 
 pub mod hunter;
 pub mod warrior;
 
+pub struct ClassPlugin;
+impl Plugin for ClassPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (apply_class_growth, grant_class_passives).in_set(InGameSet::Update),
+        );
+    }
+}
+
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum Class {
     Hunter,
     Nomad,
@@ -25,6 +42,86 @@ impl Class {
             _ => Vec::new(),
         }
     }
+
+    // Class passives, mostly what generates the class resource
+    pub fn passives(&self) -> Vec<Passive> {
+        let source = BuffSource::Passive(*self);
+        match self {
+            Class::Hunter => vec![
+                Passive::new(
+                    source,
+                    PassiveCondition::OnKill(KillFilter::Players),
+                    vec![PassiveEffect::FillResource],
+                ),
+                Passive::new(
+                    source,
+                    PassiveCondition::OnKill(KillFilter::NonPlayers),
+                    vec![PassiveEffect::Resource(1.0)],
+                ),
+            ],
+            Class::Berserker => vec![
+                Passive::new(
+                    source,
+                    PassiveCondition::OnDamageDealt,
+                    vec![PassiveEffect::Resource(1.0)],
+                ),
+                Passive::new(
+                    source,
+                    PassiveCondition::OnDamageTaken,
+                    vec![PassiveEffect::Resource(1.0)],
+                ),
+            ],
+            _ => Vec::new(),
+        }
+    }
+
+    // How much of each stat is gained per level, applied through Modifier::Scale
+    pub fn stat_growth(&self) -> Vec<(Stat, f32)> {
+        use Stat::*;
+        match self {
+            Class::Hunter => vec![
+                (HealthMax, 70.0),
+                (PhysicalPower, 8.0),
+                (PhysicalProtection, 2.5),
+                (MagicalProtection, 0.8),
+                (AttacksPerSecond, 0.015),
+            ],
+            Class::Berserker => vec![
+                (HealthMax, 85.0),
+                (HealthRegen, 0.6),
+                (PhysicalPower, 6.0),
+                (PhysicalProtection, 3.0),
+                (MagicalProtection, 0.9),
+                (AttacksPerSecond, 0.012),
+            ],
+            _ => vec![
+                (HealthMax, 75.0),
+                (PhysicalProtection, 2.7),
+                (MagicalProtection, 0.9),
+                (AttacksPerSecond, 0.01),
+            ],
+        }
+    }
+}
+
+// Growth follows the class, swapping class swaps the whole set
+fn apply_class_growth(mut actors: Query<(&Class, &mut Attributes), Changed<Class>>) {
+    for (class, mut attributes) in &mut actors {
+        attributes.remove_source(&ModifierSource::Class);
+        for (stat, growth) in class.stat_growth() {
+            attributes.set_modifier(stat.scale(), ModifierSource::Class, growth);
+        }
+    }
+}
+
+// Class passives are swapped out with the class the same way
+fn grant_class_passives(mut actors: Query<(&Class, &mut Passives), Changed<Class>>) {
+    for (class, mut passives) in &mut actors {
+        passives
+            .list
+            .retain(|passive| !matches!(passive.source, BuffSource::Passive(_)));
+        passives.list.extend(class.passives());
+    }
 }
 
 // Resource is what thematically 'fuels' each of the classes
@@ -1,26 +1,53 @@
-use std::{collections::BTreeMap, time::Duration};
+use std::{
+    collections::{BTreeMap, HashMap},
+    time::Duration,
+};
 
 use bevy::prelude::*;
+use bevy_xpbd_3d::prelude::{LinearVelocity, LockedAxes};
+use serde::Deserialize;
 
-use crate::{actor::cast::Casting, area::queue::CCEvent, assets::Icons, session::director::InGameSet};
+use crate::{
+    actor::cast::Casting,
+    area::queue::{CCEvent, CleanseEvent, ImmunityEvent},
+    assets::Icons,
+    physics::LockedAxesGameDefs,
+    session::director::InGameSet,
+    stats::{Attributes, ModifierSource, Stat},
+};
+
+// How long a target remembers being hit by a kind of CC
+const DIMINISHING_WINDOW: f32 = 6.0;
+// Duration multiplier for each repeat inside the window, running off the end grants immunity
+const DIMINISHING_STEPS: [f32; 3] = [1.0, 0.65, 0.35];
+const DIMINISHING_IMMUNITY: f32 = 3.0;
 
 pub struct CCPlugin;
 impl Plugin for CCPlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(
             FixedUpdate,
-            (tick_ccs, apply_ccs).chain().in_set(InGameSet::Pre),
+            (
+                tick_ccs,
+                apply_immunities,
+                cleanse_ccs,
+                apply_ccs,
+                displace,
+                slow_speed,
+            )
+                .chain()
+                .in_set(InGameSet::Pre),
         );
     }
 }
 
-#[derive(Debug, Clone, Reflect, Copy)]
+#[derive(Debug, Clone, Reflect, Copy, Deserialize)]
 pub struct CCInfo {
     pub cckind: CCKind,
     pub duration: f32,
 }
 
-#[derive(Debug, Clone, Copy, Eq, PartialEq, Reflect, Hash, PartialOrd, Ord)]
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Reflect, Hash, PartialOrd, Ord, Deserialize)]
 pub enum CCKind {
     Stun,
     Root,
@@ -28,8 +55,11 @@ pub enum CCKind {
     Disarm,
     Silence,
     Cripple,
-    //Slow, change to buff since affects a stat, proper CC's are for absolutes
-    // UPDATE: probably want this to be an exception, make it a hybrid because it is useful to see displayed
+    Knockback,
+    Pull,
+    Knockup,
+    // hybrid, it changes a stat like a buff but is shown and resisted like the rest of CC
+    Slow,
 }
 
 impl CCKind {
@@ -50,11 +80,78 @@ impl CCKind {
             CCKind::Disarm => "DISARMED",
             CCKind::Silence => "SILENCED",
             CCKind::Cripple => "CRIPPLED",
+            CCKind::Knockback => "KNOCKED BACK",
+            CCKind::Pull => "PULLED",
+            CCKind::Knockup => "KNOCKED UP",
+            CCKind::Slow => "SLOWED",
         };
         str.to_string()
     }
     pub fn cancels_casts(&self) -> bool {
-        self == &CCKind::Stun || self == &CCKind::Silence || self == &CCKind::Fear
+        use CCKind::*;
+        matches!(self, Stun | Silence | Fear | Knockback | Pull | Knockup)
+    }
+}
+
+/// Forced movement that comes with a CC of the matching kind.
+#[derive(Debug, Clone, Copy, Reflect, Deserialize)]
+pub enum Displacement {
+    /// Pushed straight away from the source.
+    Knockback { distance: f32 },
+    /// Dragged towards the source, never past it.
+    Pull { distance: f32 },
+    /// Thrown up in place, the vertical axis is unlocked until landing.
+    Knockup { height: f32 },
+}
+
+impl Displacement {
+    pub fn cckind(&self) -> CCKind {
+        match self {
+            Displacement::Knockback { .. } => CCKind::Knockback,
+            Displacement::Pull { .. } => CCKind::Pull,
+            Displacement::Knockup { .. } => CCKind::Knockup,
+        }
+    }
+
+    /// Shorter travel, how tenacity and diminishing returns shrink a displacement.
+    pub fn scaled(self, multiplier: f32) -> Self {
+        match self {
+            Displacement::Knockback { distance } => Displacement::Knockback {
+                distance: distance * multiplier,
+            },
+            Displacement::Pull { distance } => Displacement::Pull {
+                distance: distance * multiplier,
+            },
+            Displacement::Knockup { height } => Displacement::Knockup {
+                height: height * multiplier,
+            },
+        }
+    }
+}
+
+/// A displacement in progress, it lasts as long as its CC does.
+#[derive(Debug, Clone, Copy)]
+pub struct Displaced {
+    pub displacement: Displacement,
+    /// Horizontal travel per second.
+    pub velocity: Vec3,
+    /// Height to land back on, actors dont collide with the ground.
+    pub ground: f32,
+}
+
+/// Which kinds of CC an effect works on.
+#[derive(Debug, Clone, Reflect, Deserialize)]
+pub enum CCSelection {
+    All,
+    Only(Vec<CCKind>),
+}
+
+impl CCSelection {
+    pub fn contains(&self, kind: CCKind) -> bool {
+        match self {
+            CCSelection::All => true,
+            CCSelection::Only(kinds) => kinds.contains(&kind),
+        }
     }
 }
 
@@ -64,19 +161,59 @@ impl std::fmt::Display for CCKind {
     }
 }
 
-fn apply_ccs(mut targets_query: Query<(&mut CCMap, Option<&mut Casting>)>, mut cc_events: EventReader<CCEvent>) {
+fn apply_ccs(
+    mut targets_query: Query<(
+        &mut CCMap,
+        Option<&mut Casting>,
+        Option<&Attributes>,
+        Option<&GlobalTransform>,
+    )>,
+    mut cc_events: EventReader<CCEvent>,
+) {
     for event in cc_events.read() {
-        let Ok((mut ccs, casting_opt)) = targets_query.get_mut(event.target_entity) else { continue };
+        let Ok((mut ccs, casting_opt, attributes, transform)) = targets_query.get_mut(event.target_entity) else {
+            continue
+        };
+        let kind = event.ccinfo.cckind;
+        if ccs.is_immune(kind) {
+            continue
+        }
+        // strongest slow wins, a weaker one is ignored while it runs
+        let slow = event.slow.unwrap_or_default();
+        if kind == CCKind::Slow && ccs.slowed_by().is_some_and(|current| current > slow) {
+            continue
+        }
+        let tenacity = attributes.map(|attrs| attrs.get(Stat::Tenacity)).unwrap_or_default();
+        let reduction = (1.0 - tenacity.clamp(0.0, 100.0) / 100.0) * ccs.diminish(kind);
+        // displacements keep their timing and travel less instead, a shorter one would fling them through walls
+        let duration = match event.displacement {
+            Some(_) if reduction > 0.0 => event.ccinfo.duration,
+            _ => event.ccinfo.duration * reduction,
+        };
         // stop casting if we get CC'd
         if let Some(mut casting) = casting_opt {
             if event.ccinfo.cckind.cancels_casts() {
                 *casting = Casting::default();
             }
         }
+        if kind == CCKind::Fear {
+            ccs.fear_source = event.source;
+        }
+        if kind == CCKind::Slow {
+            ccs.slow_percent = slow;
+        }
+        if let (Some(displacement), Some(transform)) = (event.displacement, transform) {
+            ccs.displace(
+                displacement.scaled(reduction),
+                transform.translation(),
+                event.source,
+                duration,
+            );
+        }
         ccs.map.insert(
-            event.ccinfo.cckind,
+            kind,
             Timer::new(
-                Duration::from_millis((event.ccinfo.duration * 1000.0) as u64),
+                Duration::from_millis((duration * 1000.0) as u64),
                 TimerMode::Once,
             ),
         );
@@ -84,12 +221,94 @@ fn apply_ccs(mut targets_query: Query<(&mut CCMap, Option<&mut Casting>)>, mut c
     }
 }
 
+fn cleanse_ccs(mut targets_query: Query<&mut CCMap>, mut cleanse_events: EventReader<CleanseEvent>) {
+    for event in cleanse_events.read() {
+        let Ok(mut ccs) = targets_query.get_mut(event.target) else { continue };
+        ccs.map.retain(|kind, _| !event.ccs.contains(*kind));
+    }
+}
+
+fn apply_immunities(mut targets_query: Query<&mut CCMap>, mut immunity_events: EventReader<ImmunityEvent>) {
+    for event in immunity_events.read() {
+        let Ok(mut ccs) = targets_query.get_mut(event.target) else { continue };
+        // becoming immune also shakes off whatever is already applied
+        ccs.map.retain(|kind, _| !event.ccs.contains(*kind));
+        ccs.immunities.push((
+            event.ccs.clone(),
+            Timer::new(
+                Duration::from_millis((event.duration * 1000.0) as u64),
+                TimerMode::Once,
+            ),
+        ));
+    }
+}
+
 fn tick_ccs(time: Res<Time>, mut query: Query<&mut CCMap>) {
     for mut ccs in &mut query {
         ccs.map.retain(|_, timer| {
             timer.tick(time.delta());
             !timer.finished()
         });
+        ccs.immunities.retain_mut(|(_, timer)| {
+            timer.tick(time.delta());
+            !timer.finished()
+        });
+        ccs.diminishing.retain(|_, (_, window)| {
+            window.tick(time.delta());
+            !window.finished()
+        });
+    }
+}
+
+// Displaced actors have their velocity driven here instead of by their controller
+fn displace(
+    mut query: Query<(
+        &mut CCMap,
+        &mut LinearVelocity,
+        &mut LockedAxes,
+        &mut Transform,
+    )>,
+) {
+    for (mut ccs, mut velocity, mut locked, mut transform) in &mut query {
+        let Some(displaced) = ccs.displaced else { continue };
+        let Some(timer) = ccs.map.get(&displaced.displacement.cckind()) else {
+            // ran out or got cleansed, put them back on the ground
+            ccs.displaced = None;
+            velocity.0 = Vec3::ZERO;
+            *locked = LockedAxes::ACTOR;
+            transform.translation.y = displaced.ground;
+            continue
+        };
+        let rise = match displaced.displacement {
+            // vertical speed of a parabola that peaks at the height halfway through
+            Displacement::Knockup { height } => {
+                let duration = timer.duration().as_secs_f32().max(f32::EPSILON);
+                4.0 * height / duration * (1.0 - 2.0 * timer.fraction())
+            }
+            _ => 0.0,
+        };
+        if rise != 0.0 {
+            *locked = LockedAxes::ROTATION_LOCKED;
+        }
+        velocity.0 = displaced.velocity + Vec3::Y * rise;
+    }
+}
+
+// Keep the speed modifier in line with the running slow, it goes away with the CC
+fn slow_speed(mut query: Query<(&CCMap, &mut Attributes)>) {
+    let source = ModifierSource::CrowdControl(CCKind::Slow);
+    for (ccs, mut attributes) in &mut query {
+        let current = attributes.get_from(Stat::Speed.mult(), &source);
+        let wanted = ccs.slowed_by().map(|percent| -percent.clamp(0.0, 100.0));
+        if current == wanted {
+            continue
+        }
+        match wanted {
+            Some(amount) => {
+                attributes.set_modifier(Stat::Speed.mult(), source.clone(), amount);
+            }
+            None => attributes.remove_modifier(Stat::Speed.mult(), &source),
+        }
     }
 }
 
@@ -103,13 +322,98 @@ fn sort_ccs(cc_map: &mut CCMap) {
 #[derive(Component, Default, Debug, Clone)]
 pub struct CCMap {
     pub map: BTreeMap<CCKind, Timer>,
+    pub immunities: Vec<(CCSelection, Timer)>,
+    /// Times each kind landed recently, and the window before that is forgotten.
+    pub diminishing: HashMap<CCKind, (usize, Timer)>,
+    /// Where the last fear came from.
+    pub fear_source: Vec3,
+    pub displaced: Option<Displaced>,
+    /// Percent of speed taken by the running slow.
+    pub slow_percent: f32,
 }
 
 impl CCMap {
+    /// Duration multiplier for another CC of this kind, counting it towards diminishing returns.
+    fn diminish(&mut self, kind: CCKind) -> f32 {
+        let (hits, window) = self
+            .diminishing
+            .entry(kind)
+            .or_insert_with(|| (0, Timer::from_seconds(DIMINISHING_WINDOW, TimerMode::Once)));
+        let multiplier = DIMINISHING_STEPS[*hits];
+        *hits += 1;
+        window.reset();
+        if *hits >= DIMINISHING_STEPS.len() {
+            self.diminishing.remove(&kind);
+            self.immunities.push((
+                CCSelection::Only(vec![kind]),
+                Timer::from_seconds(DIMINISHING_IMMUNITY, TimerMode::Once),
+            ));
+        }
+        multiplier
+    }
+
+    /// Start moving the actor, a new displacement replaces the old one but keeps where they stood.
+    fn displace(&mut self, displacement: Displacement, position: Vec3, source: Vec3, duration: f32) {
+        let ground = self.displaced.map_or(position.y, |displaced| displaced.ground);
+        let away = Vec3::new(position.x - source.x, 0.0, position.z - source.z);
+        let distance = match displacement {
+            Displacement::Knockback { distance } => distance,
+            Displacement::Pull { distance } => -distance.min(away.length()),
+            Displacement::Knockup { .. } => 0.0,
+        };
+        let velocity = away.normalize_or_zero() * distance / duration.max(f32::EPSILON);
+        // the previous kind is done once it is replaced
+        if let Some(previous) = self.displaced {
+            let kind = previous.displacement.cckind();
+            if kind != displacement.cckind() {
+                self.map.remove(&kind);
+            }
+        }
+        self.displaced = Some(Displaced {
+            displacement,
+            velocity,
+            ground,
+        });
+    }
+
+    /// How long the current CC of this kind lasts after tenacity and diminishing returns, displacements only lose distance.
+    pub fn effective_duration(&self, kind: CCKind) -> Option<f32> {
+        self.map.get(&kind).map(|timer| timer.duration().as_secs_f32())
+    }
+
+    pub fn is_immune(&self, kind: CCKind) -> bool {
+        self.immunities.iter().any(|(selection, _)| selection.contains(kind))
+    }
     pub fn is_rooted(&self) -> bool {
         self.map.contains_key(&CCKind::Root)
     }
     pub fn is_stunned(&self) -> bool {
         self.map.contains_key(&CCKind::Stun)
     }
+    /// Percent of speed lost while a slow is running.
+    pub fn slowed_by(&self) -> Option<f32> {
+        self.map.contains_key(&CCKind::Slow).then_some(self.slow_percent)
+    }
+    pub fn is_displaced(&self) -> bool {
+        self.displaced.is_some()
+    }
+    pub fn is_feared(&self) -> bool {
+        self.map.contains_key(&CCKind::Fear)
+    }
+    pub fn is_disarmed(&self) -> bool {
+        self.map.contains_key(&CCKind::Disarm)
+    }
+    pub fn is_crippled(&self) -> bool {
+        self.map.contains_key(&CCKind::Cripple)
+    }
+    /// Casting anything at all is blocked.
+    pub fn blocks_casting(&self) -> bool {
+        self.map.keys().any(|kind| kind.cancels_casts())
+    }
+
+    /// Direction a feared actor runs, straight away from the source along the ground.
+    pub fn fear_direction(&self, position: Vec3) -> Vec3 {
+        let away = position - self.fear_source;
+        Vec3::new(away.x, 0.0, away.z).normalize_or_zero()
+    }
 }
@@ -5,6 +5,7 @@ use crate::{
     assets::Items,
     item::Item,
     prelude::InGameSet,
+    stats::{Attributes, ModifierSource},
     ui::ui_bundles::{item_image_build, BuildSlotNumber},
 };
 
@@ -15,7 +16,12 @@ impl Plugin for InventoryPlugin {
 
         app.add_systems(
             Update,
-            (update_inventory_ui, swap_inventory_slots).in_set(InGameSet::Update),
+            (
+                update_inventory_ui,
+                swap_inventory_slots,
+                update_item_modifiers,
+            )
+                .in_set(InGameSet::Update),
         );
     }
 }
@@ -45,6 +51,20 @@ fn update_inventory_ui(
     }
 }
 
+// Items give stats by slot, so buying, selling and swapping all just re-key them here
+fn update_item_modifiers(mut query: Query<(&Inventory, &mut Attributes), Changed<Inventory>>) {
+    for (inventory, mut attributes) in &mut query {
+        for (slot, item) in inventory.iter().enumerate() {
+            let source = ModifierSource::Item(slot as u8);
+            attributes.remove_source(&source);
+            let Some(item) = item else { continue };
+            for (tag, amount) in item.info().stats {
+                attributes.set_modifier(tag, source.clone(), amount);
+            }
+        }
+    }
+}
+
 fn swap_inventory_slots(
     mut inventories: Query<&mut Inventory>,
     local_entity: Option<Res<LocalPlayer>>,
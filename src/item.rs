@@ -6,7 +6,9 @@ use lazy_static::lazy_static;
 
 use crate::{
     assets::Items,
+    buff::{BuffInfo, BuffSource, BuffTargets, BuffType},
     inventory::Inventory,
+    passive::{Passive, PassiveCondition, PassiveEffect},
     stats::{AttributeTag, Stat},
 };
 
@@ -31,7 +33,7 @@ pub struct ItemInfo {
     /// Direct parts to this item.
     pub parts: Vec<Item>,
     pub stats: HashMap<AttributeTag, f32>, // should be Attribute Tag probably
-                                           // pub passives: Vec<ItemPassive>,
+    pub passives: Vec<Passive>,
 }
 
 // stuff that isn't per 'stage' of an item, downstream of hierarchy
@@ -61,6 +63,7 @@ lazy_static! {
                         (CooldownReduction.add(), 15.0),
                         (CharacterResourceMax.add(), 1.0),
                     ]),
+                    ..default()
                 }
             ),
             (
@@ -72,6 +75,7 @@ lazy_static! {
                         (PhysicalPower.add(), 60.0),
                         (CooldownReduction.add(), 15.0),
                     ]),
+                    ..default()
                 }
             ),
             (
@@ -84,6 +88,7 @@ lazy_static! {
                         (PhysicalPenetration.add(), 15.0),
                         (Speed.add(), 1.0),
                     ]),
+                    ..default()
                 }
             ),
             (
@@ -112,8 +117,20 @@ lazy_static! {
                     price: 300.0,
                     stats: HashMap::from([
                         (PhysicalProtection.add(), 20.0),
-                        (Health.add(), 100.0),
+                        (HealthMax.add(), 100.0),
+                        (Tenacity.add(), 15.0),
                     ]),
+                    passives: vec![Passive::new(
+                        BuffSource::Item(DruidStone),
+                        PassiveCondition::below_health(30.0),
+                        vec![PassiveEffect::Buff(BuffInfo {
+                            stats: vec![(PhysicalProtection.add(), 30.0), (MagicalProtection.add(), 30.0)],
+                            duration: 4.0,
+                            bufftargets: BuffTargets::Allies,
+                            bufftype: BuffType::Buff,
+                            ..BuffInfo::new("Barkskin")
+                        })],
+                    )],
                     ..default()
                 }
             ),
@@ -126,6 +143,7 @@ lazy_static! {
                         (CooldownReduction.add(), 20.0),
                     ]),
                     parts: vec![BookOfSouls, BookOfSouls],
+                    ..default()
                 }
             ),
         ])
@@ -13,13 +13,14 @@ use buff::BuffPlugin;
 use camera::{spawn_spectator_camera, CameraPlugin};
 use crowd_control::CCPlugin;
 use inventory::InventoryPlugin;
+use mobility::MobilityPlugin;
+use over_time::OverTimePlugin;
+use passive::PassivePlugin;
+use shield::ShieldPlugin;
 use stats::StatsPlugin;
 use ui::UiPlugin;
 
-use crate::{
-    classes::{hunter::HunterPlugin, warrior::WarriorPlugin},
-    session::SessionPlugin,
-};
+use crate::{classes::ClassPlugin, session::SessionPlugin};
 
 pub mod ability;
 pub mod actor;
@@ -33,10 +34,14 @@ pub mod debug;
 pub mod inventory;
 pub mod item;
 pub mod map;
+pub mod mitigation;
 pub mod mobility;
+pub mod over_time;
+pub mod passive;
 pub mod physics;
 pub mod previous;
 pub mod session;
+pub mod shield;
 pub mod stats;
 pub mod ui;
 pub mod utils;
@@ -101,8 +106,10 @@ impl Plugin for GamePlugin {
             BuffPlugin,
             StatsPlugin,
             CCPlugin,
-            WarriorPlugin,
-            HunterPlugin,
+            ShieldPlugin,
+            OverTimePlugin,
+            MobilityPlugin,
+            (PassivePlugin, ClassPlugin),
         ));
 
         app.add_systems(Startup, spawn_spectator_camera);
@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use bevy::{
     pbr::{NotShadowCaster, NotShadowReceiver},
     prelude::*,
@@ -14,8 +16,10 @@ use crate::{
     },
     buff::{BuffInfo, BuffMap, BuffTargets, BuffType},
     camera::Spectatable,
-    crowd_control::{CCInfo, CCKind, CCMap},
+    crowd_control::{CCInfo, CCKind, CCMap, CCSelection},
+    over_time::OverTimeEffects,
     prelude::{non_damaging::ObjectiveHealthOwner, *},
+    shield::Shields,
     GameState,
 };
 
@@ -98,10 +102,11 @@ pub fn setup_arena(
                 let mut attributes = Attributes::default();
                 attributes
                     .set(Stat::Health, 100.0)
-                    .set(Stat::MagicalProtection, 60.0)
-                    .set(Stat::PhysicalProtection, 60.0);
+                    .set_base(Stat::MagicalProtection, 60.0)
+                    .set_base(Stat::PhysicalProtection, 60.0);
                 attributes
             },
+            Shields::default(),
             Tower,
             Name::new("Tower"),
             Spectatable,
@@ -155,7 +160,11 @@ pub fn setup_arena(
             Spectatable,
             Name::new("Target Dummy"),
         ))
-        .insert((ActorType::Minion,))
+        .insert((
+            ActorType::Minion,
+            Shields::default(),
+            OverTimeEffects::default(),
+        ))
         .insert({
             let mut attrs = Attributes::default();
             attrs
@@ -186,7 +195,7 @@ pub fn setup_arena(
             TargetsHittable::default(),
             TEAM_NEUTRAL,
             Tags(vec![
-                TagInfo::Damage(0.0),
+                TagInfo::damage(0.0),
                 TagInfo::CC(CCInfo {
                     cckind: CCKind::Stun,
                     duration: 3.0,
@@ -212,28 +221,20 @@ pub fn setup_arena(
             Collider::cuboid(4.0, 0.3, 4.0),
             Sensor,
             Tags(vec![
-                TagInfo::Damage(27.0),
+                TagInfo::damage(27.0),
                 TagInfo::Buff(BuffInfo {
-                    stat: AttributeTag::Modifier {
-                        modifier: Modifier::Mul,
-                        target: Box::new(Stat::Speed.into()),
-                    },
-                    amount: 20.0,
+                    stats: vec![(Stat::Speed.mult(), 20.0)],
                     max_stacks: 3,
                     duration: 10.0,
                     image: Ability::Fireball.get_image(&icons),
-                    ..default()
+                    ..BuffInfo::new("Tailwind")
                 }),
                 TagInfo::Buff(BuffInfo {
-                    stat: AttributeTag::Modifier {
-                        modifier: Modifier::Add,
-                        target: Box::new(Stat::CharacterResourceMax.into()),
-                    },
-                    amount: 1.0,
+                    stats: vec![(Stat::CharacterResourceMax.add(), 1.0)],
                     max_stacks: 3,
                     duration: 10.0,
                     image: Ability::Frostbolt.get_image(&icons),
-                    ..default()
+                    ..BuffInfo::new("Focus")
                 }),
                 TagInfo::CC(CCInfo {
                     cckind: CCKind::Silence,
@@ -269,20 +270,24 @@ pub fn setup_arena(
             TargetsHittable::default(),
             TickBehavior::new_individual(1.0),
             Tags(vec![
-                TagInfo::Heal(28.0),
-                TagInfo::Damage(44.0),
+                TagInfo::heal(28.0),
+                TagInfo::damage(44.0),
+                TagInfo::Shield {
+                    amount: 40.0,
+                    scaling: HashMap::new(),
+                    duration: 4.0,
+                    blocks: None,
+                },
+                TagInfo::Cleanse(CCSelection::All),
+                TagInfo::Purify,
                 TagInfo::Buff(BuffInfo {
-                    stat: AttributeTag::Modifier {
-                        modifier: Modifier::Add,
-                        target: Box::new(Stat::PhysicalPenetration.into()),
-                    },
-                    amount: 5.0,
+                    stats: vec![(Stat::PhysicalPenetration.add(), 5.0)],
                     max_stacks: 6,
                     duration: 18.0,
                     bufftargets: BuffTargets::Allies,
                     bufftype: BuffType::Buff,
                     image: Ability::Dash.get_image(&icons),
-                    ..default()
+                    ..BuffInfo::new("Fountain's Edge")
                 }),
             ]),
         ));
@@ -0,0 +1,172 @@
+//! How protections and penetration turn incoming damage into what actually lands.
+
+use bevy::prelude::*;
+
+use crate::{
+    ability::DamageType,
+    stats::{Attributes, Stat},
+};
+
+/// The mitigation curve in use, game modes can insert their own to change how protections scale.
+#[derive(Resource, Clone, Copy)]
+pub struct Mitigation {
+    /// Fraction of damage that gets through the given (already penetrated) protections.
+    pub curve: fn(f32) -> f32,
+}
+
+impl Default for Mitigation {
+    fn default() -> Self {
+        Self { curve: diminishing }
+    }
+}
+
+impl Mitigation {
+    /// Change in health after mitigation, negative is damage and positive is healing.
+    /// Heals are never mitigated.
+    pub fn apply(&self, amount: f32, damage_type: DamageType, defender: &Attributes, attacker: &Attributes) -> f32 {
+        if amount >= 0.0 {
+            return amount
+        }
+        let protections = protections(damage_type, defender, attacker);
+        amount * (self.curve)(protections)
+    }
+}
+
+/// Protections are worth less the more you stack, 100 protections halves damage.
+pub fn diminishing(protections: f32) -> f32 {
+    100.0 / (100.0 + protections)
+}
+
+/// Every point of protections blocks 1% of damage, capped at 70%.
+pub fn linear(protections: f32) -> f32 {
+    1.0 - (protections / 100.0).clamp(0.0, 0.7)
+}
+
+/// Defender's protections against a damage type once the attacker's penetration is taken off.
+pub fn protections(damage_type: DamageType, defender: &Attributes, attacker: &Attributes) -> f32 {
+    let physical = || {
+        penetrate(
+            defender.get(Stat::PhysicalProtection),
+            attacker.get(Stat::PhysicalPenetration),
+            attacker.get(Stat::PhysicalPercentPenetration),
+        )
+    };
+    let magical = || {
+        penetrate(
+            defender.get(Stat::MagicalProtection),
+            attacker.get(Stat::MagicalPenetration),
+            attacker.get(Stat::MagicalPercentPenetration),
+        )
+    };
+    match damage_type {
+        DamageType::Physical => physical(),
+        DamageType::Magical => magical(),
+        // hybrid goes through whichever protection is weaker
+        DamageType::Hybrid => physical().min(magical()),
+        DamageType::True => 0.0,
+    }
+}
+
+/// Percent penetration applies first, then flat, and protections never go negative.
+pub fn penetrate(protections: f32, flat: f32, percent: f32) -> f32 {
+    (protections * (1.0 - percent / 100.0) - flat).max(0.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn defender(physical: f32, magical: f32) -> Attributes {
+        let mut attributes = Attributes::default();
+        attributes
+            .set(Stat::PhysicalProtection, physical)
+            .set(Stat::MagicalProtection, magical);
+        attributes
+    }
+
+    fn attacker(flat: f32, percent: f32) -> Attributes {
+        let mut attributes = Attributes::default();
+        attributes
+            .set(Stat::PhysicalPenetration, flat)
+            .set(Stat::MagicalPenetration, flat)
+            .set(Stat::PhysicalPercentPenetration, percent)
+            .set(Stat::MagicalPercentPenetration, percent);
+        attributes
+    }
+
+    #[test]
+    fn percent_penetration_before_flat() {
+        assert_eq!(penetrate(100.0, 10.0, 20.0), 70.0);
+    }
+
+    #[test]
+    fn penetration_cannot_go_negative() {
+        assert_eq!(penetrate(20.0, 50.0, 0.0), 0.0);
+        assert_eq!(penetrate(20.0, 0.0, 150.0), 0.0);
+    }
+
+    #[test]
+    fn diminishing_halves_at_100() {
+        assert_eq!(diminishing(0.0), 1.0);
+        assert_eq!(diminishing(100.0), 0.5);
+    }
+
+    #[test]
+    fn linear_is_capped() {
+        assert_eq!(linear(30.0), 0.7);
+        assert!((linear(500.0) - 0.3).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn hybrid_uses_lower_protection() {
+        let defender = defender(80.0, 40.0);
+        let attacker = attacker(0.0, 0.0);
+        assert_eq!(protections(DamageType::Hybrid, &defender, &attacker), 40.0);
+        assert_eq!(
+            protections(DamageType::Physical, &defender, &attacker),
+            80.0
+        );
+        assert_eq!(protections(DamageType::Magical, &defender, &attacker), 40.0);
+    }
+
+    #[test]
+    fn hybrid_penetrates_both_before_comparing() {
+        let defender = defender(60.0, 50.0);
+        let mut attacker = attacker(0.0, 0.0);
+        attacker.set(Stat::PhysicalPenetration, 30.0);
+        assert_eq!(protections(DamageType::Hybrid, &defender, &attacker), 30.0);
+    }
+
+    #[test]
+    fn true_damage_ignores_protections() {
+        let defender = defender(300.0, 300.0);
+        let attacker = attacker(0.0, 0.0);
+        let change = Mitigation::default().apply(-100.0, DamageType::True, &defender, &attacker);
+        assert_eq!(change, -100.0);
+    }
+
+    #[test]
+    fn damage_is_mitigated() {
+        let defender = defender(100.0, 0.0);
+        let attacker = attacker(0.0, 0.0);
+        let change = Mitigation::default().apply(-100.0, DamageType::Physical, &defender, &attacker);
+        assert_eq!(change, -50.0);
+    }
+
+    #[test]
+    fn heals_bypass_mitigation() {
+        let defender = defender(100.0, 100.0);
+        let attacker = attacker(0.0, 0.0);
+        let change = Mitigation::default().apply(40.0, DamageType::Magical, &defender, &attacker);
+        assert_eq!(change, 40.0);
+    }
+
+    #[test]
+    fn curve_can_be_swapped() {
+        let defender = defender(50.0, 0.0);
+        let attacker = attacker(0.0, 0.0);
+        let mitigation = Mitigation { curve: linear };
+        let change = mitigation.apply(-100.0, DamageType::Physical, &defender, &attacker);
+        assert_eq!(change, -50.0);
+    }
+}
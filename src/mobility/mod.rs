@@ -1,19 +1,123 @@
-use bevy::prelude::*;
+use crate::{
+    ability::builder::{MobilityKind, MobilityStage},
+    crowd_control::CCMap,
+    prelude::*,
+};
 
-#[derive(Component)]
-pub enum Mobility {
-    Dash
+pub struct MobilityPlugin;
+impl Plugin for MobilityPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(FixedUpdate, move_mobility.in_set(InGameSet::Update));
+    }
 }
 
-#[derive(Component)]
-pub struct MoveToTarget {
-    pub transform: Transform,
+/// A dash, jump or teleport in progress, the controller takes back over once it is removed.
+#[derive(Component, Debug, Clone)]
+pub struct Mobility {
+    pub kind: MobilityKind,
+    /// Jumps phase through walls on the way, see [`MobilityStage::wall_pen`].
+    pub wall_pen: bool,
+    /// Layers from before the move phased the mover, put back when it ends.
+    pub previous_layers: Option<CollisionLayers>,
+    /// Where the move ends if nothing gets in the way, also the height to land back on.
+    pub destination: Vec3,
+    /// Horizontal travel per second.
+    pub velocity: Vec3,
     pub timer: Timer,
+    /// Area that travels with the caster, gone when the move ends.
+    pub area: Option<Entity>,
 }
 
-// fn move_mobility(mut query: Query<(&mut Transform, &mut MoveToTarget)>, time: Res<Time>) {
-//     for (mut transform, mut move_to_target) in query.iter_mut() {
-//         move_to_target.timer.tick(time.delta());
-//         transform
-//     }
-// }
+impl Mobility {
+    /// Dashes and jumps head along the direction, teleports go to the reticle.
+    pub fn new(stage: &MobilityStage, start: Vec3, direction: Vec3, reticle: Vec3, area: Option<Entity>) -> Self {
+        let direction = Vec3::new(direction.x, 0.0, direction.z).normalize_or_zero();
+        let destination = match stage.kind {
+            MobilityKind::Teleport => {
+                let offset = Vec3::new(reticle.x - start.x, 0.0, reticle.z - start.z);
+                start + offset.clamp_length_max(stage.distance)
+            }
+            _ => start + direction * stage.distance,
+        };
+        Self::to(stage, start, destination, area)
+    }
+
+    /// Head straight for a set destination whatever the kind, like going back to where a cast started.
+    pub fn to(stage: &MobilityStage, start: Vec3, destination: Vec3, area: Option<Entity>) -> Self {
+        let duration = stage.duration();
+        Self {
+            kind: stage.kind,
+            wall_pen: stage.wall_pen,
+            previous_layers: None,
+            destination,
+            velocity: (destination - start) / duration.max(f32::EPSILON),
+            timer: Timer::from_seconds(duration, TimerMode::Once),
+            area,
+        }
+    }
+}
+
+fn move_mobility(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut movers: Query<(
+        Entity,
+        &mut Mobility,
+        &mut Transform,
+        &mut LinearVelocity,
+        Option<&mut LockedAxes>,
+        Option<&mut CollisionLayers>,
+        Option<&CCMap>,
+    )>,
+    mut areas: Query<&mut Transform, Without<Mobility>>,
+) {
+    for (entity, mut mobility, mut transform, mut velocity, locked, layers, cc_map) in &mut movers {
+        mobility.timer.tick(time.delta());
+        // getting stunned or knocked around cuts the move short
+        let interrupted = cc_map.is_some_and(|cc_map| cc_map.is_stunned() || cc_map.is_displaced());
+        if mobility.timer.finished() || interrupted {
+            match mobility.kind {
+                MobilityKind::Teleport if !interrupted => transform.translation = mobility.destination,
+                _ => transform.translation.y = mobility.destination.y,
+            }
+            velocity.0 = Vec3::ZERO;
+            if let Some(mut locked) = locked {
+                *locked = LockedAxes::ACTOR;
+            }
+            if let (Some(mut layers), Some(previous)) = (layers, mobility.previous_layers) {
+                *layers = previous;
+            }
+            if let Some(area) = mobility.area.and_then(|area| commands.get_entity(area)) {
+                area.despawn_recursive();
+            }
+            commands.entity(entity).remove::<Mobility>();
+            continue
+        }
+
+        let rise = match mobility.kind {
+            // vertical speed of an arc that peaks halfway through
+            MobilityKind::Jump { height, .. } => {
+                let duration = mobility.timer.duration().as_secs_f32();
+                4.0 * height / duration * (1.0 - 2.0 * mobility.timer.fraction())
+            }
+            _ => 0.0,
+        };
+        if let MobilityKind::Jump { .. } = mobility.kind {
+            if let Some(mut locked) = locked {
+                *locked = LockedAxes::ROTATION_LOCKED;
+            }
+            if let Some(mut layers) = layers {
+                if mobility.wall_pen {
+                    mobility.previous_layers.get_or_insert(*layers);
+                    *layers = CollisionLayers::PHASED;
+                }
+            }
+        }
+        velocity.0 = mobility.velocity + Vec3::Y * rise;
+
+        if let Some(Ok(mut area_transform)) = mobility.area.map(|area| areas.get_mut(area)) {
+            area_transform.translation = transform.translation;
+            area_transform.rotation = transform.rotation;
+        }
+    }
+}
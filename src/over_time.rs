@@ -0,0 +1,139 @@
+//! Damage and heals over time that live on the target instead of on a ticking area.
+
+use std::{collections::HashMap, time::Instant};
+
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::{
+    ability::{Ability, DamageType},
+    area::queue::{HealthChangeEvent, OverTimeEvent},
+    buff::{StackFalloff, StackRefresh, Stacks},
+    prelude::ActorState,
+    session::director::InGameSet,
+};
+
+pub struct OverTimePlugin;
+impl Plugin for OverTimePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (apply_over_time, tick_over_time).chain().in_set(InGameSet::Update),
+        );
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct OverTimeInfo {
+    /// Health change per tick for each stack.
+    pub amount: f32,
+    pub interval: f32,
+    pub duration: f32,
+    pub max_stacks: u32,
+    pub falloff: StackFalloff,
+    pub refresh: StackRefresh,
+}
+
+impl Default for OverTimeInfo {
+    fn default() -> Self {
+        Self {
+            amount: 10.0,
+            interval: 1.0,
+            duration: 4.0,
+            max_stacks: 1,
+            falloff: StackFalloff::All,
+            refresh: StackRefresh::All,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum OverTimeKind {
+    Damage,
+    Heal,
+}
+
+/// Who applied an effect, the same caster and ability stacks instead of adding another effect.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct OverTimeId {
+    pub caster: Entity,
+    pub ability: Ability,
+    pub kind: OverTimeKind,
+}
+
+#[derive(Debug, Clone)]
+pub struct OverTimeApplied {
+    pub info: OverTimeInfo,
+    pub damage_type: DamageType,
+    pub sensor: Entity,
+    pub stacks: Stacks,
+    pub tick: Timer,
+}
+
+#[derive(Component, Default, Debug, Clone)]
+pub struct OverTimeEffects {
+    pub map: HashMap<OverTimeId, OverTimeApplied>,
+}
+
+fn apply_over_time(mut targets_query: Query<&mut OverTimeEffects>, mut over_time_events: EventReader<OverTimeEvent>) {
+    for event in over_time_events.read() {
+        let Ok(mut effects) = targets_query.get_mut(event.target) else { continue };
+        let id = OverTimeId {
+            caster: event.caster,
+            ability: event.ability,
+            kind: event.kind,
+        };
+        if let Some(applied) = effects.map.get_mut(&id) {
+            let info = &event.info;
+            applied.stacks.add(info.duration, info.max_stacks, &info.refresh);
+            continue
+        }
+        effects.map.insert(
+            id,
+            OverTimeApplied {
+                info: event.info.clone(),
+                damage_type: event.damage_type,
+                sensor: event.sensor,
+                stacks: Stacks::new(event.info.duration),
+                tick: Timer::from_seconds(event.info.interval, TimerMode::Repeating),
+            },
+        );
+    }
+}
+
+fn tick_over_time(
+    time: Res<Time>,
+    mut query: Query<(Entity, &mut OverTimeEffects, &ActorState)>,
+    mut health_events: EventWriter<HealthChangeEvent>,
+) {
+    for (target, mut effects, state) in &mut query {
+        if state.is_dead() {
+            effects.map.clear();
+            continue
+        }
+        effects.map.retain(|id, applied| {
+            applied.tick.tick(time.delta());
+            // health goes out with the stacks that were up during the interval, before any fall off
+            for _ in 0..applied.tick.times_finished_this_tick() {
+                let amount = applied.info.amount * applied.stacks.count() as f32;
+                health_events.send(HealthChangeEvent {
+                    amount: match id.kind {
+                        OverTimeKind::Damage => -amount,
+                        OverTimeKind::Heal => amount,
+                    },
+                    damage_type: applied.damage_type,
+                    ability: id.ability,
+                    attacker: id.caster,
+                    defender: target,
+                    sensor: applied.sensor,
+                    is_crit: false,
+                    is_area: false,
+                    when: Instant::now(),
+                });
+            }
+            applied.stacks.tick(time.delta(), &applied.info.falloff);
+            !applied.stacks.is_empty()
+        });
+    }
+}
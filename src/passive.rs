@@ -0,0 +1,294 @@
+//! Passives from classes and items, a condition to watch for and the effects it sets off.
+
+use std::time::Instant;
+
+use bevy::utils::HashMap;
+
+use crate::{
+    ability::{db::AbilityRegistry, Ability, DamageType},
+    actor::{cast::AbilitySlots, rank::AbilityRanks, KillEvent},
+    area::queue::{BuffEvent, HealthChangeEvent},
+    buff::{BuffId, BuffInfo, BuffMap, BuffSource},
+    prelude::*,
+};
+
+pub struct PassivePlugin;
+impl Plugin for PassivePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                grant_item_passives,
+                grant_ability_passives,
+                detect_passives,
+                fire_passives,
+            )
+                .chain()
+                .in_set(InGameSet::Update),
+        );
+    }
+}
+
+#[derive(Component, Default, Debug, Clone)]
+pub struct Passives {
+    pub list: Vec<Passive>,
+}
+
+impl Passives {
+    /// Count another trigger for every passive waiting on a matching condition.
+    fn trigger(&mut self, matches: impl Fn(&PassiveCondition) -> bool) {
+        for passive in self.list.iter_mut() {
+            if matches(&passive.condition) {
+                passive.pending += 1;
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Passive {
+    pub source: BuffSource,
+    pub condition: PassiveCondition,
+    pub effects: Vec<PassiveEffect>,
+    /// Times the condition was met since the effects last went out.
+    pending: u32,
+}
+
+impl Passive {
+    pub fn new(source: BuffSource, condition: PassiveCondition, effects: Vec<PassiveEffect>) -> Self {
+        Self {
+            source,
+            condition,
+            effects,
+            pending: 0,
+        }
+    }
+
+    /// Every buff this passive gives is currently on the actor.
+    fn buffs_up(&self, buffs: Option<&BuffMap>, actor: Entity) -> bool {
+        self.effects.iter().all(|effect| {
+            let PassiveEffect::Buff(info) = effect else { return true };
+            let id = BuffId {
+                source: self.source,
+                caster: actor,
+                name: info.name.clone(),
+            };
+            buffs.is_some_and(|buffs| buffs.contains(&id))
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum PassiveCondition {
+    /// Keeps its buffs up, fires again whenever one of them falls off.
+    Always,
+    OnKill(KillFilter),
+    OnDamageDealt,
+    OnDamageTaken,
+    /// Fires each time health drops below the percent of max health.
+    BelowHealth {
+        percent: f32,
+        below: bool,
+    },
+    Every(Timer),
+}
+
+impl PassiveCondition {
+    pub fn below_health(percent: f32) -> Self {
+        Self::BelowHealth { percent, below: false }
+    }
+
+    pub fn every(seconds: f32) -> Self {
+        Self::Every(Timer::from_seconds(seconds, TimerMode::Repeating))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KillFilter {
+    Any,
+    Players,
+    NonPlayers,
+}
+
+impl KillFilter {
+    fn matches(&self, is_player: bool) -> bool {
+        match self {
+            KillFilter::Any => true,
+            KillFilter::Players => is_player,
+            KillFilter::NonPlayers => !is_player,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum PassiveEffect {
+    Buff(BuffInfo),
+    Resource(f32),
+    FillResource,
+    Heal(f32),
+}
+
+// Item passives follow the inventory, passives of items still held keep their state
+fn grant_item_passives(mut query: Query<(&Inventory, &mut Passives), Changed<Inventory>>) {
+    for (inventory, mut passives) in &mut query {
+        let mut held: HashMap<Item, usize> = HashMap::new();
+        for item in inventory.items() {
+            *held.entry(item).or_default() += 1;
+        }
+        // drop the passives of copies that left the inventory
+        let mut seen: HashMap<Item, usize> = HashMap::new();
+        passives.list.retain(|passive| {
+            let BuffSource::Item(item) = passive.source else { return true };
+            let seen = seen.entry(item).or_default();
+            *seen += 1;
+            *seen <= held.get(&item).copied().unwrap_or_default() * item.info().passives.len()
+        });
+        // then add them for copies that are new
+        for (item, copies) in held {
+            let item_passives = item.info().passives;
+            if item_passives.is_empty() {
+                continue
+            }
+            let kept = seen
+                .get(&item)
+                .copied()
+                .unwrap_or_default()
+                .min(copies * item_passives.len());
+            for _ in kept / item_passives.len()..copies {
+                passives.list.extend(item_passives.iter().cloned());
+            }
+        }
+    }
+}
+
+// Learned abilities with a passive stage keep its buff up, picked again whenever ranks or slots change
+fn grant_ability_passives(
+    mut query: Query<(&AbilitySlots, &AbilityRanks, &mut Passives), Or<(Changed<AbilitySlots>, Changed<AbilityRanks>)>>,
+    registry: Res<AbilityRegistry>,
+) {
+    for (slots, ranks, mut passives) in &mut query {
+        passives
+            .list
+            .retain(|passive| !matches!(passive.source, BuffSource::Ability(_)));
+        for ability in slots.abilities() {
+            if !ranks.is_learned(&ability) {
+                continue
+            }
+            let Some(buff) = registry.info(&ability, ranks.get(&ability)).passive() else { continue };
+            passives.list.push(Passive::new(
+                BuffSource::Ability(ability),
+                PassiveCondition::Always,
+                vec![PassiveEffect::Buff(buff.clone())],
+            ));
+        }
+    }
+}
+
+fn detect_passives(
+    time: Res<Time>,
+    mut kill_events: EventReader<KillEvent>,
+    mut health_events: EventReader<HealthChangeEvent>,
+    mut actors: Query<(
+        Entity,
+        &mut Passives,
+        &Attributes,
+        &ActorState,
+        Option<&BuffMap>,
+    )>,
+    actor_types: Query<&ActorType>,
+) {
+    for event in kill_events.read() {
+        let Ok((_, mut passives, ..)) = actors.get_mut(event.killer) else { continue };
+        let is_player = matches!(actor_types.get(event.damned), Ok(ActorType::Player(_)));
+        passives.trigger(|condition| match condition {
+            PassiveCondition::OnKill(filter) => filter.matches(is_player),
+            _ => false,
+        });
+    }
+    for event in health_events.read() {
+        // only damage between two different actors counts, not heals or hurting yourself
+        if event.amount >= 0.0 || event.attacker == event.defender {
+            continue
+        }
+        if let Ok((_, mut passives, ..)) = actors.get_mut(event.attacker) {
+            passives.trigger(|condition| matches!(condition, PassiveCondition::OnDamageDealt));
+        }
+        if let Ok((_, mut passives, ..)) = actors.get_mut(event.defender) {
+            passives.trigger(|condition| matches!(condition, PassiveCondition::OnDamageTaken));
+        }
+    }
+    for (entity, mut passives, attributes, state, buffs) in &mut actors {
+        if state.is_dead() {
+            continue
+        }
+        let health = attributes.get(Stat::Health) / attributes.get(Stat::HealthMax).max(1.0) * 100.0;
+        for passive in passives.list.iter_mut() {
+            match passive.condition {
+                PassiveCondition::Always => {
+                    if !passive.buffs_up(buffs, entity) {
+                        passive.pending = 1;
+                    }
+                }
+                PassiveCondition::BelowHealth { percent, ref mut below } => {
+                    let now_below = health < percent;
+                    if now_below && !*below {
+                        passive.pending += 1;
+                    }
+                    *below = now_below;
+                }
+                PassiveCondition::Every(ref mut timer) => {
+                    timer.tick(time.delta());
+                    passive.pending += timer.times_finished_this_tick();
+                }
+                _ => (),
+            }
+        }
+    }
+}
+
+fn fire_passives(
+    mut actors: Query<(Entity, &mut Passives, &mut Attributes)>,
+    mut buff_events: EventWriter<BuffEvent>,
+    mut health_events: EventWriter<HealthChangeEvent>,
+) {
+    for (entity, mut passives, mut attributes) in &mut actors {
+        for passive in passives.list.iter_mut() {
+            for _ in 0..std::mem::take(&mut passive.pending) {
+                for effect in passive.effects.iter() {
+                    match effect {
+                        PassiveEffect::Buff(info) => {
+                            buff_events.send(BuffEvent {
+                                info: info.clone(),
+                                target: entity,
+                                source: passive.source,
+                                caster: entity,
+                            });
+                        }
+                        PassiveEffect::Resource(amount) => {
+                            let max = attributes.get(Stat::CharacterResourceMax);
+                            let resource = attributes.get_mut(Stat::CharacterResource);
+                            *resource = (*resource + amount).clamp(0.0, max);
+                        }
+                        PassiveEffect::FillResource => {
+                            let max = attributes.get(Stat::CharacterResourceMax);
+                            attributes.set(Stat::CharacterResource, max);
+                        }
+                        PassiveEffect::Heal(amount) => {
+                            health_events.send(HealthChangeEvent {
+                                amount: *amount,
+                                damage_type: DamageType::True,
+                                ability: Ability::default(),
+                                attacker: entity,
+                                defender: entity,
+                                sensor: entity,
+                                is_crit: false,
+                                is_area: false,
+                                when: Instant::now(),
+                            });
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -12,6 +12,7 @@ pub trait CollisionLayersGameDefs {
     const WALL: CollisionLayers;
     const GROUND: CollisionLayers;
     const FLUFF: CollisionLayers;
+    const PHASED: CollisionLayers;
 }
 
 impl CollisionLayersGameDefs for CollisionLayers {
@@ -20,6 +21,8 @@ impl CollisionLayersGameDefs for CollisionLayers {
     const WALL: CollisionLayers = CollisionLayers::from_bits(WALL, u32::MAX);
     const ABILITY: CollisionLayers = CollisionLayers::from_bits(ABILITY, PLAYER | WALL);
     const FLUFF: CollisionLayers = CollisionLayers::from_bits(FLUFF, u32::MAX);
+    // players passing over walls
+    const PHASED: CollisionLayers = CollisionLayers::from_bits(PLAYER, PLAYER | ABILITY);
 }
 
 pub trait LockedAxesGameDefs {
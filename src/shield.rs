@@ -0,0 +1,96 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+use crate::{ability::DamageType, area::queue::ShieldEvent, session::director::InGameSet};
+
+pub struct ShieldPlugin;
+impl Plugin for ShieldPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            FixedUpdate,
+            (tick_shields, apply_shields).chain().in_set(InGameSet::Pre),
+        );
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct ShieldInfo {
+    pub amount: f32,
+    pub duration: f32,
+    /// Only soaks this damage type, None soaks everything.
+    pub blocks: Option<DamageType>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Shield {
+    pub amount: f32,
+    pub blocks: Option<DamageType>,
+    pub timer: Timer,
+}
+
+impl Shield {
+    pub fn blocks(&self, damage_type: DamageType) -> bool {
+        self.blocks.map_or(true, |blocks| blocks == damage_type)
+    }
+}
+
+#[derive(Component, Default, Debug, Clone)]
+pub struct Shields {
+    pub list: Vec<Shield>,
+}
+
+impl Shields {
+    pub fn total(&self) -> f32 {
+        self.list.iter().map(|shield| shield.amount).sum()
+    }
+
+    /// Soak up as much of the damage as the shields allow, returns how much was absorbed.
+    pub fn absorb(&mut self, damage: f32, damage_type: DamageType) -> f32 {
+        let mut remaining = damage;
+        // typed shields go first so a generic shield isnt wasted on damage they could take
+        let mut order = (0..self.list.len()).collect::<Vec<_>>();
+        order.sort_by_key(|index| self.list[*index].blocks.is_none());
+        for index in order {
+            if remaining <= 0.0 {
+                break
+            }
+            let shield = &mut self.list[index];
+            if !shield.blocks(damage_type) {
+                continue
+            }
+            let soaked = shield.amount.min(remaining);
+            shield.amount -= soaked;
+            remaining -= soaked;
+        }
+        self.list.retain(|shield| shield.amount > 0.0);
+        damage - remaining
+    }
+}
+
+fn apply_shields(mut targets_query: Query<&mut Shields>, mut shield_events: EventReader<ShieldEvent>) {
+    for event in shield_events.read() {
+        let Ok(mut shields) = targets_query.get_mut(event.target) else { continue };
+        // whole numbers so the amount predicted in calculate_health_change matches what gets consumed
+        shields.list.push(Shield {
+            amount: event.info.amount.round(),
+            blocks: event.info.blocks,
+            timer: Timer::new(
+                Duration::from_millis((event.info.duration * 1000.0) as u64),
+                TimerMode::Once,
+            ),
+        });
+    }
+}
+
+fn tick_shields(time: Res<Time>, mut query: Query<&mut Shields>) {
+    for mut shields in &mut query {
+        if shields.list.is_empty() {
+            continue
+        }
+        shields.list.retain_mut(|shield| {
+            shield.timer.tick(time.delta());
+            !shield.timer.finished()
+        });
+    }
+}
@@ -5,6 +5,7 @@ use bevy::{
     utils::{HashMap, HashSet},
 };
 use lazy_static::lazy_static;
+use serde::Deserialize;
 use strum::IntoEnumIterator;
 use strum_macros::EnumIter;
 
@@ -13,15 +14,20 @@ use crate::{
     ability::{Ability, DamageType},
     actor::player::Player,
     area::queue::HealthChangeEvent,
+    buff::BuffId,
+    crowd_control::CCKind,
+    mitigation::Mitigation,
     prelude::{ActorState, Icons},
     previous::previous,
     session::director::InGameSet,
+    shield::Shields,
 };
 
 pub struct StatsPlugin;
 impl Plugin for StatsPlugin {
     fn build(&self, app: &mut App) {
         app.add_event::<HealthMitigatedEvent>();
+        app.init_resource::<Mitigation>();
 
         app.register_type::<Vec<String>>();
         app.register_type::<Attributes>();
@@ -39,6 +45,7 @@ impl Plugin for StatsPlugin {
                     regen_resource,
                     calculate_health_change,
                     apply_health_change,
+                    lifesteal,
                 )
                     .chain(),
                 spool_gold,
@@ -50,33 +57,13 @@ impl Plugin for StatsPlugin {
     }
 }
 
-fn calculate_attributes(mut attributes: Query<&mut Attributes, Changed<Attributes>>) {
+fn calculate_attributes(mut attributes: Query<&mut Attributes>) {
     for mut attributes in &mut attributes {
-        let mut tags = attributes.attrs.keys().cloned().collect::<Vec<_>>();
-        tags.sort_by(|a, b| a.ordering().cmp(&b.ordering()));
-
-        for tag in tags {
-            match tag.clone() {
-                AttributeTag::Modifier { modifier, target } => {
-                    let modifier_attr = attributes.get(tag);
-                    //let level = *attributes.clone().get(&Stat::Level.into()).unwrap_or(&1.0);\
-                    let old = attributes.get_mut(*target);
-
-                    let new = match modifier {
-                        Modifier::Base => modifier_attr,
-                        //Modifier::Scale => *old + level * modifier_attr,
-                        Modifier::Add => *old + modifier_attr,
-                        Modifier::Sub => *old - modifier_attr,
-                        Modifier::Mul => *old * (modifier_attr + 100.0) / 100.0,
-                        Modifier::Div => *old / (modifier_attr + 100.0) * 100.0,
-                        Modifier::Min => old.max(modifier_attr),
-                        Modifier::Max => old.min(modifier_attr),
-                    };
-                    *old = new;
-                }
-                AttributeTag::Stat(_) => (),
-            }
+        // check before borrowing mutably so this system doesnt trip change detection on clean attributes
+        if attributes.dirty.is_empty() {
+            continue
         }
+        attributes.recalculate();
     }
 }
 
@@ -84,12 +71,15 @@ fn regen_health(mut query: Query<&mut Attributes>, time: Res<Time>) {
     for mut attributes in query.iter_mut() {
         let regen = attributes.get(Stat::HealthRegen);
         let max = attributes.get(Stat::HealthMax);
-        let health = attributes.get_mut(Stat::Health);
-        if *health <= 0.0 {
+        let health = attributes.get(Stat::Health);
+        if health <= 0.0 {
             continue
         }
-        let result = *health + (regen * time.delta_seconds());
-        *health = result.clamp(0.0, max);
+        let result = (health + (regen * time.delta_seconds())).clamp(0.0, max);
+        // only borrow mutably when health moves, so full actors dont trip change detection
+        if result != health {
+            attributes.set(Stat::Health, result);
+        }
     }
 }
 
@@ -97,9 +87,11 @@ fn regen_resource(mut query: Query<&mut Attributes>, time: Res<Time>) {
     for mut attributes in query.iter_mut() {
         let regen = attributes.get(Stat::CharacterResourceRegen);
         let max = attributes.get(Stat::CharacterResourceMax);
-        let resource = attributes.get_mut(Stat::CharacterResource);
-        let result = *resource + (regen * time.delta_seconds());
-        *resource = result.clamp(0.0, max);
+        let resource = attributes.get(Stat::CharacterResource);
+        let result = (resource + (regen * time.delta_seconds())).clamp(0.0, max);
+        if result != resource {
+            attributes.set(Stat::CharacterResource, result);
+        }
     }
 }
 
@@ -115,7 +107,11 @@ fn calculate_health_change(
     mut health_events: EventReader<HealthChangeEvent>,
     mut health_mitigated_events: EventWriter<HealthMitigatedEvent>,
     attribute_query: Query<&Attributes>,
+    shield_query: Query<&Shields>,
+    mitigation: Res<Mitigation>,
 ) {
+    // shields are only consumed in apply_health_change, track what this batch has used up so far
+    let mut shields_left: HashMap<Entity, Shields> = HashMap::new();
     for event in health_events.read() {
         let Ok(defender_stats) = attribute_query.get(event.defender) else { continue };
         let attacker_stats = if let Ok(attacker_stats) = attribute_query.get(event.attacker) {
@@ -124,33 +120,34 @@ fn calculate_health_change(
             Attributes::default() // can prob optimize this later
         };
 
-        let (prots, pen) = if event.damage_type == DamageType::Physical {
-            (
-                defender_stats.get(Stat::PhysicalProtection),
-                attacker_stats.get(Stat::PhysicalPenetration),
-            )
-        } else if event.damage_type == DamageType::Magical {
-            (
-                defender_stats.get(Stat::MagicalProtection),
-                attacker_stats.get(Stat::MagicalPenetration),
-            )
-        } else {
-            (0.0, 0.0)
-        };
-        let percent_pen = 10.0;
-        let prots_penned = prots * percent_pen / 100.0 + pen;
-        let post_mitigation_damage = (event.amount * 100.0 / (100.0 + prots - prots_penned)).ceil() as i32;
+        let change = mitigation.apply(
+            event.amount,
+            event.damage_type,
+            defender_stats,
+            &attacker_stats,
+        );
+        let post_mitigation_damage = change.ceil() as i32;
         // ceil = round up, so damage gets -1 and healing gets +1, might use floor to
         // nerf healing if op LOL
-        let mitigated = (event.amount as i32 - post_mitigation_damage).abs() as u32;
+        let mut absorbed = 0;
+        if post_mitigation_damage < 0 {
+            if let Ok(shields) = shield_query.get(event.defender) {
+                let shields = shields_left.entry(event.defender).or_insert_with(|| shields.clone());
+                absorbed = shields.absorb(-post_mitigation_damage as f32, event.damage_type) as i32;
+            }
+        }
+        let mitigated = (event.amount as i32 - post_mitigation_damage).abs() as u32 + absorbed as u32;
         health_mitigated_events.send(HealthMitigatedEvent {
-            change: post_mitigation_damage,
+            change: post_mitigation_damage + absorbed,
             mitigated: mitigated,
+            absorbed: absorbed as u32,
             ability: event.ability,
             attacker: event.attacker,
             defender: event.defender,
             sensor: event.sensor,
             damage_type: event.damage_type,
+            is_crit: event.is_crit,
+            is_area: event.is_area,
             when: event.when,
         });
     }
@@ -158,10 +155,16 @@ fn calculate_health_change(
 
 fn apply_health_change(
     mut health_mitigated_events: EventReader<HealthMitigatedEvent>,
-    mut health_query: Query<(&mut ActorState, &mut Attributes)>,
+    mut health_query: Query<(&mut ActorState, &mut Attributes, Option<&mut Shields>)>,
 ) {
     for event in health_mitigated_events.read() {
-        let Ok((mut actor_state, mut defender_stats)) = health_query.get_mut(event.defender) else { continue };
+        let Ok((mut actor_state, mut defender_stats, shields)) = health_query.get_mut(event.defender) else { continue };
+        // shields take their part of the hit before health does
+        if let Some(mut shields) = shields {
+            if event.absorbed > 0 {
+                shields.absorb(event.absorbed as f32, event.damage_type);
+            }
+        }
         let health = defender_stats.get_mut(Stat::Health);
         /*
         if event.change > 0 {
@@ -178,10 +181,54 @@ fn apply_health_change(
     }
 }
 
+// Areas that can hit a crowd only return part of the lifesteal
+const AREA_LIFESTEAL_RATIO: f32 = 0.33;
+
+// Heal attackers for a percent of the damage that actually landed, the heal goes back
+// through calculate_health_change so it shows up in logs like any other heal
+fn lifesteal(
+    mut health_mitigated_events: EventReader<HealthMitigatedEvent>,
+    mut health_events: EventWriter<HealthChangeEvent>,
+    attribute_query: Query<&Attributes>,
+) {
+    for event in health_mitigated_events.read() {
+        if event.change >= 0 || event.attacker == event.defender {
+            continue
+        }
+        let Ok(attacker_stats) = attribute_query.get(event.attacker) else { continue };
+        let physical = attacker_stats.get(Stat::PhysicalLifesteal);
+        let magical = attacker_stats.get(Stat::MagicalLifesteal);
+        let mut percent = match event.damage_type {
+            DamageType::Physical => physical,
+            DamageType::Magical => magical,
+            DamageType::Hybrid | DamageType::True => physical.max(magical),
+        };
+        if event.is_area {
+            percent *= AREA_LIFESTEAL_RATIO;
+        }
+        let heal = -event.change as f32 * percent / 100.0;
+        if heal <= 0.0 {
+            continue
+        }
+        health_events.send(HealthChangeEvent {
+            amount: heal,
+            damage_type: event.damage_type,
+            ability: event.ability,
+            attacker: event.attacker,
+            defender: event.attacker,
+            sensor: event.sensor,
+            is_crit: false,
+            is_area: false,
+            when: Instant::now(),
+        });
+    }
+}
+
 // Stats that the character has in the bottom left
 // Let be customizable later
 lazy_static! {
     pub static ref LISTED_STATS: Vec<Stat> = vec![
+        Stat::Level,
         Stat::Gold,
         Stat::PhysicalPower,
         Stat::PhysicalPenetration,
@@ -190,7 +237,7 @@ lazy_static! {
     ];
 }
 
-#[derive(Reflect, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, EnumIter)]
+#[derive(Reflect, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, EnumIter, Deserialize)]
 #[reflect(Debug, PartialEq)]
 pub enum Stat {
     // Temporal
@@ -214,7 +261,14 @@ pub enum Stat {
     MagicalProtection,
     PhysicalPenetration,
     MagicalPenetration,
+    PhysicalPercentPenetration,
+    MagicalPercentPenetration,
     AttacksPerSecond,
+    CritChance,
+    CritDamage,
+    PhysicalLifesteal,
+    MagicalLifesteal,
+    Tenacity,
     CooldownReduction,
 }
 
@@ -233,6 +287,9 @@ impl Stat {
             MagicalPower => 45.0,
             PhysicalPower => 200.0,
             CooldownReduction => 50.0,
+            CritDamage => 150.0, // percent of the hit a crit deals
+            // everyone ignores a bit of protections, as mitigation always has
+            PhysicalPercentPenetration | MagicalPercentPenetration => 10.0,
             _ => 0.0,
         }
     }
@@ -264,6 +321,24 @@ impl Stat {
             target: Box::new(self.into()),
         }
     }
+    pub fn scale(self) -> AttributeTag {
+        AttributeTag::Modifier {
+            modifier: Modifier::Scale,
+            target: Box::new(self.into()),
+        }
+    }
+    pub fn base(self) -> AttributeTag {
+        AttributeTag::Modifier {
+            modifier: Modifier::Base,
+            target: Box::new(self.into()),
+        }
+    }
+
+    // Pools like health are spent and refilled directly, everything else is built from modifiers
+    pub fn has_modifier_stack(self) -> bool {
+        use Stat::*;
+        !matches!(self, Health | CharacterResource | Gold | Xp | Level)
+    }
 }
 
 impl Display for Stat {
@@ -282,7 +357,14 @@ impl Display for Stat {
             Stat::MagicalProtection => "Spell Shield",
             Stat::PhysicalPenetration => "Armor Penetration",
             Stat::MagicalPenetration => "Spell Pierce",
+            Stat::PhysicalPercentPenetration => "Armor Shred",
+            Stat::MagicalPercentPenetration => "Spell Shred",
             Stat::AttacksPerSecond => "Attack Speed",
+            Stat::CritChance => "Critical Strike Chance",
+            Stat::CritDamage => "Critical Strike Damage",
+            Stat::PhysicalLifesteal => "Physical Lifesteal",
+            Stat::MagicalLifesteal => "Magical Lifesteal",
+            Stat::Tenacity => "Tenacity",
             Stat::CooldownReduction => "Cooldown Reduction",
             Stat::Level => "Level",
             Stat::Health => "Health",
@@ -292,7 +374,7 @@ impl Display for Stat {
     }
 }
 
-#[derive(Reflect, Debug, Default, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Reflect, Debug, Default, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize)]
 #[reflect(Debug, Default, PartialEq)]
 pub enum Modifier {
     #[default]
@@ -301,18 +383,43 @@ pub enum Modifier {
     Sub,
     Div,
     Base,
-    //Scale,
+    Scale,
     Max,
     Min,
 }
 
+impl Modifier {
+    /// Merge two sources of this modifier into one amount.
+    fn combine(&self, a: f32, b: f32) -> f32 {
+        match self {
+            Modifier::Min => a.max(b),
+            Modifier::Max => a.min(b),
+            _ => a + b,
+        }
+    }
+
+    fn apply(&self, old: f32, amount: f32, level: f32) -> f32 {
+        match self {
+            Modifier::Base => amount,
+            // base is the level 1 value, so growth starts at level 2
+            Modifier::Scale => old + (level - 1.0).max(0.0) * amount,
+            Modifier::Add => old + amount,
+            Modifier::Sub => old - amount,
+            Modifier::Mul => old * (amount + 100.0) / 100.0,
+            Modifier::Div => old / (amount + 100.0) * 100.0,
+            Modifier::Min => old.max(amount),
+            Modifier::Max => old.min(amount),
+        }
+    }
+}
+
 impl Display for Modifier {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         write!(f, "{:?}", self)
     }
 }
 
-#[derive(Clone, Eq, PartialEq, PartialOrd, Ord, Hash, Reflect)]
+#[derive(Clone, Eq, PartialEq, PartialOrd, Ord, Hash, Reflect, Deserialize)]
 #[reflect(Debug, Default, PartialEq)]
 pub enum AttributeTag {
     Modifier {
@@ -346,9 +453,6 @@ impl std::fmt::Debug for AttributeTag {
     }
 }
 
-// only returning the modifier for the id, not the modifier and stat
-// if an ability changes 2 different stats in the same way, add or mult, itll
-// break probably (?)
 impl Display for AttributeTag {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         match self {
@@ -366,7 +470,7 @@ impl AttributeTag {
             Self::Modifier { modifier, .. } => match modifier {
                 // do these first
                 Modifier::Base => 1,
-                //Modifier::Scale => 2, // stats you get per level
+                Modifier::Scale => 2, // stats you get per level
                 Modifier::Add => 3,
                 Modifier::Sub => 3, // Move to after div for reduction of stats?
 
@@ -387,91 +491,243 @@ impl AttributeTag {
             AttributeTag::Stat(stat) => *stat,
         }
     }
+
+    /// The modifier entry this tag contributes to, plain stats count as flat additions.
+    /// Pools like health have no modifier stack so they return None.
+    pub fn as_modifier(&self) -> Option<AttributeTag> {
+        match self {
+            AttributeTag::Modifier { .. } => Some(self.clone()),
+            AttributeTag::Stat(stat) if stat.has_modifier_stack() => Some(stat.add()),
+            AttributeTag::Stat(_) => None,
+        }
+    }
+}
+
+/// Where a modifier came from, so it can be removed again without touching anyone else's.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ModifierSource {
+    Base,
+    Class,
+    Item(u8), // inventory slot
+    Buff(BuffId),
+    CrowdControl(CCKind),
+}
+
+impl Display for ModifierSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ModifierSource::Base => write!(f, "Base"),
+            ModifierSource::Class => write!(f, "Class"),
+            ModifierSource::Item(slot) => write!(f, "Item {}", slot + 1),
+            ModifierSource::Buff(id) => write!(f, "{}", id),
+            ModifierSource::CrowdControl(kind) => write!(f, "{}", kind),
+        }
+    }
 }
 
 #[derive(Component, Debug, Clone, Reflect)]
 pub struct Attributes {
     dirty: HashSet<AttributeTag>,
     attrs: HashMap<AttributeTag, f32>,
+    // every source contributing to a modifier, in the order they were added
+    #[reflect(ignore)]
+    modifiers: HashMap<AttributeTag, Vec<(ModifierSource, f32)>>,
 }
 
 impl Attributes {
+    /// Final value of a stat, or the summed sources of a modifier.
     pub fn get(&self, tag: impl Into<AttributeTag>) -> f32 {
-        self.attrs.get(&tag.into()).cloned().unwrap_or(0.0)
+        let tag = tag.into();
+        match tag {
+            AttributeTag::Modifier { .. } => self
+                .modifiers
+                .get(&tag)
+                .map(|sources| sources.iter().map(|(_, amount)| amount).sum())
+                .unwrap_or(0.0),
+            AttributeTag::Stat(_) => self.attrs.get(&tag).cloned().unwrap_or(0.0),
+        }
     }
 
-    pub fn get_mut(&mut self, tag: impl Into<AttributeTag>) -> &mut f32 {
-        let tag = tag.into();
+    /// What a single source adds to a modifier, None if it adds nothing.
+    pub fn get_from(&self, tag: impl Into<AttributeTag>, source: &ModifierSource) -> Option<f32> {
+        let sources = self.modifiers.get(&tag.into())?;
+        sources.iter().find(|(old, _)| old == source).map(|(_, amount)| *amount)
+    }
+
+    pub fn get_mut(&mut self, stat: Stat) -> &mut f32 {
+        let tag = AttributeTag::from(stat);
         self.dirty.insert(tag.clone());
         self.attrs.entry(tag).or_insert(0.0)
     }
 
-    pub fn set(&mut self, tag: impl Into<AttributeTag>, amount: f32) -> &mut Self {
-        let tag = tag.into();
-        *self.get_mut(tag) = amount;
+    pub fn set(&mut self, stat: Stat, amount: f32) -> &mut Self {
+        *self.get_mut(stat) = amount;
         self
     }
 
-    pub fn set_base(&mut self, tag: impl Into<AttributeTag>, amount: f32) -> &mut Self {
+    pub fn set_base(&mut self, stat: Stat, amount: f32) -> &mut Self {
+        self.set_modifier(stat.base(), ModifierSource::Base, amount)
+    }
+
+    /// Add or replace the amount a source gives to a modifier.
+    pub fn set_modifier(&mut self, tag: impl Into<AttributeTag>, source: ModifierSource, amount: f32) -> &mut Self {
         let tag = tag.into();
-        *self.get_mut(tag.clone()) = amount;
-        let base_tag = AttributeTag::Modifier {
-            modifier: Modifier::Base,
-            target: Box::new(tag),
+        let Some(tag) = tag.as_modifier() else {
+            warn!("{:?} has no modifier stack", tag);
+            return self
         };
-        //info!("{:?}: {:?}", base_tag, amount);
-        *self.get_mut(base_tag) = amount;
+        let sources = self.modifiers.entry(tag.clone()).or_default();
+        match sources.iter_mut().find(|(old, _)| *old == source) {
+            Some((_, old_amount)) => *old_amount = amount,
+            None => sources.push((source, amount)),
+        }
+        self.dirty.insert(tag);
         self
     }
 
-    pub fn add_stats(&mut self, changes: impl Iterator<Item = (impl Into<AttributeTag>, f32)>) {
-        for (stat, change) in changes {
-            let current = self.get_mut(stat);
-            *current += change;
+    pub fn remove_modifier(&mut self, tag: impl Into<AttributeTag>, source: &ModifierSource) {
+        let Some(tag) = tag.into().as_modifier() else { return };
+        let Some(sources) = self.modifiers.get_mut(&tag) else { return };
+        sources.retain(|(old, _)| old != source);
+        self.dirty.insert(tag);
+    }
+
+    /// Remove everything a source gives, like when an item is sold.
+    pub fn remove_source(&mut self, source: &ModifierSource) {
+        for (tag, sources) in self.modifiers.iter_mut() {
+            let before = sources.len();
+            sources.retain(|(old, _)| old != source);
+            if sources.len() != before {
+                self.dirty.insert(tag.clone());
+            }
+        }
+    }
+
+    // Only recalculates the stats touched since last time
+    fn recalculate(&mut self) {
+        let dirty = std::mem::take(&mut self.dirty);
+        let mut stats = dirty.iter().map(|tag| tag.target_stat()).collect::<HashSet<_>>();
+        // level changes what every Scale modifier is worth
+        if stats.contains(&Stat::Level) {
+            for tag in self.modifiers.keys() {
+                if let AttributeTag::Modifier {
+                    modifier: Modifier::Scale,
+                    ..
+                } = tag
+                {
+                    stats.insert(tag.target_stat());
+                }
+            }
+        }
+        for stat in stats {
+            let Some(value) = self.calculate(stat) else { continue };
+            self.attrs.insert(stat.into(), value);
+        }
+    }
+
+    /// Run a stat's modifiers in order, None if nothing has ever modified it.
+    fn calculate(&self, stat: Stat) -> Option<f32> {
+        if !self.modifiers.keys().any(|tag| tag.target_stat() == stat) {
+            return None
         }
+        let total = self.breakdown(stat).last().map(|step| step.total).unwrap_or(0.0);
+        Some(total)
     }
 
-    pub fn remove_stats(&mut self, changes: impl Iterator<Item = (impl Into<AttributeTag>, f32)>) {
-        for (stat, change) in changes {
-            let current = self.get_mut(stat);
-            *current -= change;
+    /// Every step of a stat's modifier stack in the order it is applied, to explain where the final value came from.
+    pub fn breakdown(&self, stat: Stat) -> Vec<StatContribution> {
+        let mut stack = self
+            .modifiers
+            .iter()
+            .filter(|(tag, _)| tag.target_stat() == stat)
+            .collect::<Vec<_>>();
+        stack.sort_by(|(a, _), (b, _)| a.ordering().cmp(&b.ordering()).then(a.cmp(b)));
+
+        let level = self.get(Stat::Level);
+        let mut total = 0.0;
+        let mut steps = Vec::new();
+        for (tag, sources) in stack {
+            let AttributeTag::Modifier { modifier, .. } = tag else { continue };
+            // sources of the same modifier combine before applying, so percents dont compound
+            let Some(amount) = sources
+                .iter()
+                .map(|(_, amount)| *amount)
+                .reduce(|a, b| modifier.combine(a, b))
+            else {
+                continue
+            };
+            total = modifier.apply(total, amount, level);
+            for (source, amount) in sources {
+                steps.push(StatContribution {
+                    modifier: modifier.clone(),
+                    source: source.clone(),
+                    amount: *amount,
+                    total,
+                });
+            }
         }
+        steps
+    }
+}
+
+/// One step of a stat's modifier stack, see [`Attributes::breakdown`].
+#[derive(Clone, Debug)]
+pub struct StatContribution {
+    pub modifier: Modifier,
+    pub source: ModifierSource,
+    pub amount: f32,
+    /// Value of the stat once every source of this modifier is applied.
+    pub total: f32,
+}
+
+impl Display for StatContribution {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let amount = self.amount;
+        let change = match self.modifier {
+            Modifier::Base => format!("{}", amount),
+            Modifier::Scale => format!("+{} per level", amount),
+            Modifier::Add => format!("+{}", amount),
+            Modifier::Sub => format!("-{}", amount),
+            Modifier::Mul => format!("+{}%", amount),
+            Modifier::Div => format!("-{}%", amount),
+            Modifier::Min => format!("at least {}", amount),
+            Modifier::Max => format!("at most {}", amount),
+        };
+        write!(f, "{} {} = {}", self.source, change, self.total.round())
     }
 }
 
 impl Default for Attributes {
     fn default() -> Self {
-        let mut map: HashMap<AttributeTag, f32> = HashMap::new();
+        let mut attributes = Self {
+            dirty: default(),
+            attrs: default(),
+            modifiers: default(),
+        };
         for stat in Stat::iter() {
-            use Stat::*;
-            match stat {
-                Health | CharacterResource | Gold => continue, // these stats dont need modifier stack, add xp and level?
-                _ => (),
+            if stat.has_modifier_stack() {
+                attributes.set_base(stat, stat.get_base());
+            } else {
+                attributes.attrs.insert(stat.into(), stat.get_base());
             }
-            map.insert(
-                AttributeTag::Modifier {
-                    modifier: Modifier::Base,
-                    target: Box::new(stat.clone().into()),
-                },
-                stat.get_base(),
-            );
-        }
-
-        Self {
-            dirty: default(),
-            attrs: map,
         }
+        attributes
     }
 }
 
 #[derive(Event, Clone)]
 pub struct HealthMitigatedEvent {
     pub change: i32,
+    /// Damage prevented by protections and shields together.
     pub mitigated: u32,
+    /// The part of mitigated that shields soaked up.
+    pub absorbed: u32,
     pub ability: Ability,
     pub attacker: Entity,
     pub defender: Entity,
     pub sensor: Entity,
     pub damage_type: DamageType,
+    pub is_crit: bool,
+    pub is_area: bool,
     pub when: Instant,
 }
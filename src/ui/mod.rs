@@ -5,6 +5,7 @@ use crate::{
     assets::{Fonts, Images},
     camera::PlayerCam,
     session::director::InGameSet,
+    shield::Shields,
     stats::{AttributeTag, Attributes, Stat},
     ui::{
         holding::{HoldingPlugin, Reposition},
@@ -47,6 +48,7 @@ impl Plugin for UiPlugin {
                 tick_despawn_timers,
                 follow_in_3d,
                 bar_track,
+                shield_bar_track,
                 text_track,
                 add_base_ui,
                 //show_floating_health_bars.run_if(resource_exists::<Spectating>()),
@@ -277,6 +279,7 @@ pub fn spawn_floating_health_bars(
                             max: Stat::HealthMax.into(),
                         },
                     ));
+                    parent.spawn(shield_fill(entity));
                 });
             });
     }
@@ -305,6 +308,21 @@ pub fn bar_track(query: Query<&Attributes, Changed<Attributes>>, mut bar_query:
     }
 }
 
+pub fn shield_bar_track(query: Query<(&Attributes, &Shields)>, mut bar_query: Query<(&mut Style, &ShieldBar)>) {
+    for (mut style, shield_bar) in &mut bar_query {
+        let Ok((attributes, shields)) = query.get(shield_bar.0) else { continue };
+        let max = attributes.get(Stat::HealthMax);
+        if max <= 0.0 {
+            continue
+        }
+        let health = (attributes.get(Stat::Health) / max).clamp(0.0, 1.0);
+        let shield = (shields.total() / max).clamp(0.0, 1.0);
+        // starts where health ends, sliding back over the health when the bar runs out of room
+        style.left = Val::Percent(health.min(1.0 - shield) * 100.0);
+        style.width = Val::Percent(shield * 100.0);
+    }
+}
+
 #[derive(Component)]
 pub struct TextTrack {
     pub entity: Entity,
@@ -6,8 +6,9 @@ use crate::{
     actor::player::{LocalPlayerId, Player},
     assets::Fonts,
     inventory::Inventory,
-    prelude::{InGameSet, Team, TEAM_1},
+    prelude::{ActorType, InGameSet, Team, TEAM_1},
     session::team::TeamRoster,
+    stats::HealthMitigatedEvent,
     ui::ui_bundles::{plain_text, scoreboard_entry, KDAText, PersonalKDA, ScoreboardUI},
 };
 
@@ -18,7 +19,7 @@ impl Plugin for ScoreboardPlugin {
 
         app.add_systems(
             Update,
-            (populate_scoreboard, update_kda).in_set(InGameSet::Update),
+            (populate_scoreboard, update_kda, log_health_changes).in_set(InGameSet::Update),
         );
     }
 }
@@ -69,6 +70,32 @@ fn update_kda(
     }
 }
 
+fn log_health_changes(
+    mut health_mitigated_events: EventReader<HealthMitigatedEvent>,
+    actors: Query<&ActorType>,
+    mut scoreboard: ResMut<Scoreboard>,
+) {
+    for event in health_mitigated_events.read() {
+        let amount = event.change.unsigned_abs();
+        if let Ok(ActorType::Player(attacker)) = actors.get(event.attacker) {
+            let logs = &mut scoreboard.0.entry(*attacker).or_default().logs;
+            if event.change > 0 {
+                logs.healing_dealt += amount;
+            } else {
+                logs.damage_dealt += amount;
+            }
+        }
+        if event.change > 0 {
+            continue
+        }
+        if let Ok(ActorType::Player(defender)) = actors.get(event.defender) {
+            let logs = &mut scoreboard.0.entry(*defender).or_default().logs;
+            logs.damage_taken += amount;
+            logs.damage_mitigated += event.mitigated;
+        }
+    }
+}
+
 #[derive(Resource, Default)]
 pub struct Scoreboard(pub HashMap<Player, PlayerInfo>);
 
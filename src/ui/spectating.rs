@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use bevy::{audio::Volume, prelude::*, ui::RelativeCursorPosition};
 use bevy_tweening::TweenCompleted;
 
@@ -7,6 +9,7 @@ use crate::{
         cast::{AbilitySlots, CooldownMap, Tower},
         log::{LogHit, LogSide, LogType},
         player::{LocalPlayer, LocalPlayerId, Player},
+        rank::AbilityRanks,
     },
     area::queue::area_apply_tags,
     assets::{Audio, Fonts, Icons, Images, Items},
@@ -18,7 +21,10 @@ use crate::{
     prelude::{ActorState, ActorType, InGameSet, Previous},
     session::{director::Respawns, team::*},
     stats::*,
-    ui::{store::CATEGORIES, tooltip::Hoverable, ui_bundles::*, BarTrack, ButtonAction, TextTrack, FURY, GRAY},
+    ui::{
+        store::CATEGORIES, tooltip::Hoverable, ui_bundles::*, BarTrack, ButtonAction, TextTrack, CRIT_COLOR, FURY,
+        GRAY, RECAST_COLOR,
+    },
 };
 
 pub fn build_spectating(app: &mut App) {
@@ -35,6 +41,7 @@ pub fn build_spectating(app: &mut App) {
             // update_cast_bar,
             add_ability_icons,
             update_cooldowns,
+            update_ability_ranks,
             add_buffs,
             update_buff_timers,
             update_buff_stacks,
@@ -90,6 +97,7 @@ fn add_player_ui(
                             parent
                                 .spawn(bar_fill(Color::rgb(0.27, 0.77, 0.26)))
                                 .insert(BarTrack::hp(entity));
+                            parent.spawn(shield_fill(entity));
                             parent.spawn(bar_text_wrapper()).with_children(|parent| {
                                 parent
                                     .spawn(custom_text(&fonts, 18.0, -1.0))
@@ -148,9 +156,11 @@ fn add_player_ui(
                                     for stat in LISTED_STATS.iter() {
                                         // add stat name or icon?
                                         //parent.spawn(plain_text(format!("stat {}", x), 16, &fonts));
-                                        parent
-                                            .spawn(plain_text("0", 16, &fonts))
-                                            .insert(TextTrack::new(entity, stat.clone()));
+                                        parent.spawn(plain_text("0", 16, &fonts)).insert((
+                                            TextTrack::new(entity, stat.clone()),
+                                            Hoverable::Stat(entity, stat.clone()),
+                                            Interaction::default(),
+                                        ));
                                     }
                                 });
                                 parent.spawn(inventory_and_kda()).with_children(|parent| {
@@ -306,13 +316,26 @@ fn update_cc_bar(
     player: Option<Res<LocalPlayer>>,
     cc_maps: Query<&CCMap>,
     mut cc_bar_fill: Query<&mut Style, With<CCBarSelfFill>>,
+    mut cc_text: Query<&mut Text, With<CCSelfLabel>>,
 ) {
     let Some(player) = player else { return };
     let Ok(cc_of_spectating) = cc_maps.get(**player) else { return };
-    let cc_vec = Vec::from_iter(cc_of_spectating.map.clone());
-    let Some((_, cc_timer)) = cc_vec.get(0) else { return };
+    let Some((top_cc, cc_timer)) = cc_of_spectating.map.iter().next() else { return };
     let Ok(mut bar) = cc_bar_fill.get_single_mut() else { return };
     bar.width = Val::Percent(cc_timer.fraction_remaining() * 100.0);
+    // timers already hold the duration after tenacity and diminishing returns
+    let Ok(mut text) = cc_text.get_single_mut() else { return };
+    let duration = cc_of_spectating.effective_duration(*top_cc).unwrap_or_default();
+    let label = match cc_of_spectating.slowed_by() {
+        Some(percent) if *top_cc == CCKind::Slow => format!("{} {:.0}%", top_cc.to_text(), percent),
+        _ => top_cc.to_text(),
+    };
+    text.sections[0].value = format!(
+        "{} {:.1} / {:.1}",
+        label,
+        cc_timer.remaining_secs(),
+        duration
+    );
 }
 
 // fn toggle_cast_bar(
@@ -426,8 +449,10 @@ fn add_ability_icons(
                 .id();
 
             let cd_text = commands.spawn((cd_text(&fonts), ability.clone())).id();
+            let rank_text = commands.spawn((rank_text(&fonts), ability.clone())).id();
 
             commands.entity(cd_text).set_parent(ability_icon);
+            commands.entity(rank_text).set_parent(ability_icon);
             commands.entity(ability_icon).set_parent(entity);
         }
     }
@@ -444,8 +469,10 @@ fn update_cooldowns(
     // tick existing cooldowns
     let Ok(cooldowns) = cooldown_query.get(**player) else { return };
     for (mut text, ability, _) in text_query.iter_mut() {
-        if cooldowns.map.contains_key(ability) {
-            let Some(timer) = cooldowns.map.get(ability) else { continue };
+        // an open recast shows how long is left to use it
+        if let Some(window) = cooldowns.recasts.get(ability) {
+            text.sections[0].value = format!("{:.1}", window.timer.remaining_secs());
+        } else if let Some(timer) = cooldowns.map.get(ability) {
             let newcd = timer.remaining_secs() as u32;
             text.sections[0].value = newcd.to_string();
         }
@@ -455,7 +482,9 @@ fn update_cooldowns(
         for (mut text, ability, parent) in text_query.iter_mut() {
             let Ok(mut background_color) = image_query.get_mut(parent.get()) else { continue };
 
-            if cooldowns_changed.map.contains_key(ability) {
+            if cooldowns_changed.recasts.contains_key(ability) {
+                *background_color = RECAST_COLOR.into();
+            } else if cooldowns_changed.map.contains_key(ability) {
                 *background_color = Color::rgb(0.2, 0.2, 0.2).into();
             } else {
                 text.sections[0].value = String::from("");
@@ -465,6 +494,36 @@ fn update_cooldowns(
     }
 }
 
+fn update_ability_ranks(
+    player: Option<Res<LocalPlayer>>,
+    ranks_query: Query<Ref<AbilityRanks>>,
+    mut text_query: Query<(&mut Text, &Ability), With<AbilityRankText>>,
+) {
+    let Some(player) = player else { return };
+    let Ok(ranks) = ranks_query.get(**player) else { return };
+    for (mut text, ability) in text_query.iter_mut() {
+        // newly spawned icons need filling in too
+        if !ranks.is_changed() && !text.is_added() {
+            continue
+        }
+        let rank = ranks.map.get(ability).cloned().unwrap_or_default();
+        let learned = ranks.is_learned(ability);
+        text.sections[0].value = if learned {
+            format!("{}/{}", rank.current, rank.max)
+        } else {
+            format!("-/{}", rank.max)
+        };
+        // highlight slots that a point can be spent on, grey out ones that can't be cast yet
+        text.sections[0].style.color = if ranks.can_rank_up(ability) {
+            Color::YELLOW
+        } else if !learned {
+            Color::GRAY
+        } else {
+            Color::WHITE
+        };
+    }
+}
+
 fn add_buffs(
     mut commands: Commands,
     mut buff_events: EventReader<BuffAddEvent>,
@@ -519,7 +578,7 @@ fn update_buff_timers(
 fn update_buff_stacks(
     player: Option<Res<LocalPlayer>>,
     mut stack_events: EventReader<BuffStackEvent>,
-    mut buff_holders: Query<(Entity, &BuffId, &mut DespawnTimer)>,
+    mut buff_holders: Query<(Entity, &BuffUiId, &mut DespawnTimer)>,
     children_query: Query<&Children>,
     mut stacks: Query<(&mut Text, &mut Visibility), With<BuffStackNumber>>,
 ) {
@@ -532,13 +591,18 @@ fn update_buff_stacks(
             if buff_id.id != stack_change.id {
                 continue
             }
-            despawn_timer.0.reset();
+            // stacks can fall off one at a time, so follow whatever is left instead of resetting
+            let duration = despawn_timer.0.duration();
+            let remaining = Duration::from_secs_f32(stack_change.remaining).min(duration);
+            despawn_timer.0.set_elapsed(duration - remaining);
             for descendant in children_query.iter_descendants(buff_ui_entity) {
                 let Ok((mut text, mut vis)) = stacks.get_mut(descendant) else { continue };
                 text.sections[0].value = stack_change.stacks.to_string();
-                if stack_change.stacks != 1 {
-                    *vis = Visibility::Visible;
-                }
+                *vis = if stack_change.stacks > 1 {
+                    Visibility::Visible
+                } else {
+                    Visibility::Hidden
+                };
             }
             break // return cus we found the buff, dont return cus we want to go
                   // to next event
@@ -585,15 +649,25 @@ fn spawn_floating_damage(
         }
         let Ok(damaged) = damaged_query.get(damage_instance.defender) else { continue };
         let mut color = Color::WHITE;
-        if damage_instance.change == 0 {
+        let mut size = 25.0;
+        if damage_instance.change == 0 && damage_instance.absorbed > 0 {
+            text = "absorbed".to_owned();
+            color = Color::GRAY;
+        } else if damage_instance.change == 0 {
             text = "immune".to_owned();
         } else if damage_instance.change > 0 {
             color = Color::GREEN;
         } else if damage_instance.defender == *player {
             color = Color::RED;
+        } else if damage_instance.is_crit {
+            color = CRIT_COLOR;
+        }
+        if damage_instance.is_crit && damage_instance.change != 0 {
+            text.push('!');
+            size = 35.0;
         }
         commands.spawn(follow_wrapper(damaged)).with_children(|parent| {
-            parent.spawn(follow_inner_text(text, &fonts, color));
+            parent.spawn(follow_inner_text(text, &fonts, color, size));
         });
     }
 }
@@ -679,8 +753,14 @@ fn update_damage_log_ui(
                             parent.spawn(thin_image(image));
                             parent.spawn(plain_text(name, 16, &fonts));
                             parent.spawn(plain_text("dealt".to_string(), 14, &fonts));
+                            let change_color = if event.is_crit { CRIT_COLOR } else { Color::WHITE };
                             parent
-                                .spawn(plain_text((change as u32).to_string(), 18, &fonts))
+                                .spawn(color_text(
+                                    (change as u32).to_string(),
+                                    18,
+                                    &fonts,
+                                    change_color,
+                                ))
                                 .insert((
                                     EntryText::Change,
                                     StoredNumber(change as i32),
@@ -729,6 +809,10 @@ fn update_damage_log_ui(
                         EntryText::Change => {
                             added = number.0 + event.change.abs() as i32;
                             text.sections[0].value = added.to_string();
+                            // a stack with any crits in it stays highlighted
+                            if event.is_crit {
+                                text.sections[0].style.color = CRIT_COLOR;
+                            }
                         }
                         EntryText::Mitigated => {
                             added = number.0 + event.mitigated as i32;
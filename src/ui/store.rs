@@ -242,9 +242,7 @@ fn try_buy_item(
         if wallet > discounted_price {
             // remove components
             for item in event.item.common_parts(inventory.items()) {
-                if inventory.take(item) {
-                    attributes.remove_stats(item.info().stats.into_iter());
-                }
+                inventory.take(item);
             }
 
             if inventory.insert(event.item) {
@@ -252,7 +250,6 @@ fn try_buy_item(
                 let gold = attributes.get_mut(Stat::Gold);
                 *gold -= discounted_price;
                 buffer.insert(event.item);
-                attributes.add_stats(event.item.info().stats.into_iter());
                 if event.fresh {
                     history.insert(*event);
                 }
@@ -284,8 +281,6 @@ fn try_sell_item(
 
         let refund = event.item.total_price();
         if inventory.take(event.item) {
-            attributes.remove_stats(event.item.info().stats.into_iter());
-
             let gold = attributes.get_mut(Stat::Gold);
             let sell_price = if buffer.take_fresh(event.item) {
                 refund
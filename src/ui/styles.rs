@@ -11,3 +11,5 @@ pub const NEUTRAL_COLOR: Color = Color::rgb(0.7, 0.7, 0.2);
 
 pub const GRAY: Color = Color::rgba(0.05, 0.05, 0.1, 0.9);
 pub const FURY: Color = Color::rgb(0.94, 0.1, 0.2);
+pub const CRIT_COLOR: Color = Color::rgb(1.0, 0.55, 0.1);
+pub const RECAST_COLOR: Color = Color::rgb(0.3, 0.8, 0.9);
@@ -1,11 +1,13 @@
 use bevy::{prelude::*, window::PrimaryWindow};
 
 use crate::{
-    ability::Ability,
+    ability::{builder::AbilityInfo, db::AbilityRegistry, Ability},
+    actor::player::LocalPlayer,
     assets::{Fonts, Items},
     buff::{BuffInfo, BuffType},
     item::Item,
     prelude::Icons,
+    stats::{Attributes, Stat},
     ui::{
         buff_image, color_text,
         holding::Reposition,
@@ -50,12 +52,25 @@ fn load_tooltip(
     icons: Res<Icons>,
     items: Res<Items>,
     fonts: Res<Fonts>,
+    registry: Res<AbilityRegistry>,
+    local_player: Option<Res<LocalPlayer>>,
+    attributes: Query<&Attributes>,
 ) {
     let Ok((tooltip, mut vis, children, tt_ent)) = tooltip.get_single_mut() else { return };
     if let Some(hovered) = tooltip.0 {
         let Ok(hovered_info) = hoverables.get(hovered) else { return };
         despawn_children(&mut commands, children);
-        let child = hovered_info.spawn_ui(&mut commands, &icons, &items, &fonts);
+        // items explain the stats they give against the local player's current ones
+        let local_attributes = local_player.and_then(|player| attributes.get(**player).ok());
+        let child = hovered_info.spawn_ui(
+            &mut commands,
+            &icons,
+            &items,
+            &fonts,
+            &registry,
+            &attributes,
+            local_attributes,
+        );
         commands.entity(tt_ent).add_child(child);
     } else {
         *vis = Visibility::Hidden;
@@ -104,10 +119,20 @@ pub enum Hoverable {
     Item(Item),
     Ability(Ability),
     Buff(BuffInfo),
+    Stat(Entity, Stat),
 }
 
 impl Hoverable {
-    fn spawn_ui(&self, commands: &mut Commands, icons: &Res<Icons>, items: &Res<Items>, fonts: &Res<Fonts>) -> Entity {
+    fn spawn_ui(
+        &self,
+        commands: &mut Commands,
+        icons: &Res<Icons>,
+        items: &Res<Items>,
+        fonts: &Res<Fonts>,
+        registry: &AbilityRegistry,
+        attributes: &Query<&Attributes>,
+        local_attributes: Option<&Attributes>,
+    ) -> Entity {
         match self {
             Hoverable::Item(item) => {
                 let image = item.get_image(&items);
@@ -121,26 +146,72 @@ impl Hoverable {
                         for (stat, amount) in info.stats {
                             let line = format!("+ {} {}", amount, stat);
                             parent.spawn(tooltip_desc(line, &fonts));
+                            let Some(local_attributes) = local_attributes else { continue };
+                            for step in local_attributes.breakdown(stat.target_stat()) {
+                                parent.spawn(color_text(step.to_string(), 12, &fonts, Color::GRAY));
+                            }
                         }
                     })
                     .id()
             }
             Hoverable::Ability(ability) => {
                 let image = ability.get_image(&icons);
+                let definition = registry.get(ability);
+                let blueprint = registry.blueprint(ability);
+                let per_rank = |value: &dyn Fn(&AbilityInfo) -> f32| {
+                    blueprint
+                        .ranks()
+                        .map(|info| value(info).round().to_string())
+                        .collect::<Vec<_>>()
+                        .join(" / ")
+                };
+                let cooldowns = blueprint
+                    .ranks()
+                    .map(|info| info.cooldown.to_string())
+                    .collect::<Vec<_>>()
+                    .join(" / ");
+                let damage = per_rank(&|info| info.base_damage());
+                let scaling = blueprint
+                    .at_rank(1)
+                    .damage_scaling()
+                    .iter()
+                    .map(|(stat, percent)| format!("+{}% {}", percent, stat))
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                let scaled_damage = local_attributes.map(|attrs| per_rank(&|info| info.scaled_damage(attrs)));
                 commands
                     .spawn(tooltip_bg())
                     .with_children(|parent| {
                         parent.spawn(tooltip_image(image, 64));
-                        parent.spawn(plain_text(ability.get_name(), 30, &fonts));
-                        parent.spawn(tooltip_desc(ability.get_description(), &fonts));
-                        parent.spawn(plain_text(ability.get_cooldown().to_string(), 14, &fonts));
+                        parent.spawn(plain_text(definition.name.clone(), 30, &fonts));
+                        parent.spawn(tooltip_desc(definition.description.clone(), &fonts));
+                        parent.spawn(plain_text(
+                            format!("Damage: {} {}", damage, scaling),
+                            14,
+                            &fonts,
+                        ));
+                        if let Some(scaled_damage) = scaled_damage {
+                            parent.spawn(color_text(
+                                format!("Scaled: {}", scaled_damage),
+                                14,
+                                &fonts,
+                                Color::GOLD,
+                            ));
+                        }
+                        parent.spawn(plain_text(format!("Cooldown: {}", cooldowns), 14, &fonts));
                     })
                     .id()
             }
             Hoverable::Buff(info) => {
+                let stats = info
+                    .stats
+                    .iter()
+                    .map(|(stat, amount)| format!("{} {}", amount, stat))
+                    .collect::<Vec<_>>()
+                    .join(", ");
                 let description = format!(
-                    "Gives {} {}. Lasts {} seconds. Max {} stacks.",
-                    info.amount, info.stat, info.duration, info.max_stacks
+                    "Gives {}. Lasts {} seconds. Max {} stacks.",
+                    stats, info.duration, info.max_stacks
                 );
                 let is_buff = info.bufftype == BuffType::Buff;
                 commands
@@ -154,6 +225,19 @@ impl Hoverable {
                     })
                     .id()
             }
+            Hoverable::Stat(entity, stat) => {
+                let Ok(attributes) = attributes.get(*entity) else { return commands.spawn(tooltip_bg()).id() };
+                commands
+                    .spawn(tooltip_bg())
+                    .with_children(|parent| {
+                        let title = format!("{}: {}", stat, attributes.get(*stat).round());
+                        parent.spawn(plain_text(title, 22, &fonts));
+                        for step in attributes.breakdown(*stat) {
+                            parent.spawn(plain_text(step.to_string(), 14, &fonts));
+                        }
+                    })
+                    .id()
+            }
         }
     }
 }
@@ -9,6 +9,7 @@ use rand::Rng;
 
 use crate::{
     assets::{Fonts, Icons, Images, Items},
+    buff::BuffId,
     crowd_control::CCKind,
     item::{Item, ITEM_DB},
     stats::Stat,
@@ -146,6 +147,9 @@ pub struct ResourceBarText;
 #[derive(Component, Debug)]
 pub struct CooldownIconText;
 
+#[derive(Component, Debug)]
+pub struct AbilityRankText;
+
 #[derive(Component, Debug)]
 pub struct InGameClock;
 #[derive(Component, Debug)]
@@ -442,7 +446,7 @@ pub fn follow_wrapper(entity: Entity) -> impl Bundle {
     )
 }
 
-pub fn follow_inner_text(damage: String, fonts: &Res<Fonts>, color: Color) -> impl Bundle {
+pub fn follow_inner_text(damage: String, fonts: &Res<Fonts>, color: Color, size: f32) -> impl Bundle {
     let mut rng = rand::thread_rng();
     let top_offset = 40.;
     let start_horizontal = rng.gen_range(-30..30);
@@ -491,7 +495,7 @@ pub fn follow_inner_text(damage: String, fonts: &Res<Fonts>, color: Color) -> im
                 damage.to_string(),
                 TextStyle {
                     font: fonts.exo_semibold.clone(),
-                    font_size: 25.,
+                    font_size: size,
                     color: text_color,
                 },
             ),
@@ -948,11 +952,11 @@ pub fn debuff_bar() -> impl Bundle {
 }
 
 #[derive(Component)]
-pub struct BuffId {
-    pub id: String,
+pub struct BuffUiId {
+    pub id: BuffId,
 }
 
-pub fn buff_holder(time: f32, id: String) -> impl Bundle {
+pub fn buff_holder(time: f32, id: BuffId) -> impl Bundle {
     (
         NodeBundle {
             style: Style {
@@ -966,7 +970,7 @@ pub fn buff_holder(time: f32, id: String) -> impl Bundle {
             },
             ..default()
         },
-        BuffId { id },
+        BuffUiId { id },
         DespawnTimer(Timer::new(
             Duration::from_millis((time * 1000.0) as u64),
             TimerMode::Once,
@@ -1217,6 +1221,30 @@ pub fn cd_text(fonts: &Res<Fonts>) -> impl Bundle {
     )
 }
 
+pub fn rank_text(fonts: &Res<Fonts>) -> impl Bundle {
+    (
+        TextBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                bottom: Val::Px(-16.0),
+                left: Val::Px(2.0),
+                ..default()
+            },
+            text: Text::from_section(
+                "",
+                TextStyle {
+                    font: fonts.exo_bold.clone(),
+                    font_size: 14.0,
+                    color: Color::WHITE,
+                },
+            ),
+            ..default()
+        },
+        AbilityRankText,
+        Name::new("Rank Text"),
+    )
+}
+
 pub fn cast_bar_holder() -> impl Bundle {
     (
         NodeBundle {
@@ -2124,6 +2152,26 @@ pub struct ObjectiveHealthFill;
 #[derive(Component)]
 pub struct HealthBar;
 
+// Shield on top of an entity's health bar
+#[derive(Component)]
+pub struct ShieldBar(pub Entity);
+
+pub fn shield_fill(entity: Entity) -> impl Bundle {
+    (
+        NodeBundle {
+            style: Style {
+                width: Val::Percent(0.),
+                height: Val::Percent(100.),
+                position_type: PositionType::Absolute,
+                ..default()
+            },
+            background_color: Color::rgba(0.9, 0.9, 0.95, 0.8).into(),
+            ..default()
+        },
+        ShieldBar(entity),
+    )
+}
+
 pub fn bar_fill(color: Color) -> impl Bundle {
     (NodeBundle {
         style: Style {